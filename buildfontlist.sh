@@ -1,8 +1,18 @@
 #!/usr/bin/env bash
-# buildfontlist.sh — Generate fontlist.json from the Google Fonts metadata.
+# buildfontlist.sh — Generate the font catalog from the Google Fonts metadata.
 #
 # Fetches the full catalog (~2MB JSON), sorts by popularity score, and
-# outputs a JSON array of family name strings.
+# writes fontlist-v2.json, a JSON array of family entries:
+#
+#   { "family": "Fira Code", "category": "mono",
+#     "subsets": ["cyrillic", "greek", "latin", ...],
+#     "weights": [300, 400, 500, 600, 700], "variable": true }
+#
+# Categories are serif, sans, mono, display and handwriting.
+#
+# fontlist.json keeps the original format, a plain array of family names in
+# the same order, because released plyx versions fetch it and can only parse
+# that. Newer versions fetch fontlist-v2.json.
 #
 # Requires: curl, jq
 #
@@ -11,15 +21,33 @@
 set -euo pipefail
 
 METADATA_URL="https://fonts.google.com/metadata/fonts"
-OUTPUT="fontlist.json"
+OUTPUT="fontlist-v2.json"
+LEGACY_OUTPUT="fontlist.json"
 
 echo "Fetching Google Fonts metadata..."
 RAW=$(curl -sS "$METADATA_URL")
 
-echo "Sorting by popularity and extracting family names..."
+echo "Sorting by popularity and extracting family metadata..."
 echo "$RAW" \
-    | jq '[.familyMetadataList | sort_by(.popularity) | .[].family]' \
+    | jq '[.familyMetadataList | sort_by(.popularity) | .[] | {
+        family,
+        category: (.category | ascii_downcase
+            | if . == "sans serif" then "sans"
+              elif . == "monospace" then "mono"
+              else . end),
+        subsets: [.subsets[] | select(. != "menu")],
+        weights: ([.fonts | keys[] | select(endswith("i") | not) | tonumber] | unique),
+        variable: ((.axes // []) | length > 0)
+    }]' \
     > "$OUTPUT"
 
+# Search filters need the metadata; don't ship a catalog without it.
+if ! jq -e 'all(.[]; .category != null and (.subsets | length) > 0)' "$OUTPUT" > /dev/null; then
+    echo "Error: some entries in $OUTPUT have no category or subsets" >&2
+    exit 1
+fi
+
+jq '[.[].family]' "$OUTPUT" > "$LEGACY_OUTPUT"
+
 COUNT=$(jq 'length' "$OUTPUT")
-echo "Done — wrote $COUNT fonts to $OUTPUT"
+echo "Done — wrote $COUNT fonts to $OUTPUT and $LEGACY_OUTPUT"
@@ -1,4 +1,4 @@
-use crate::fonts;
+use crate::fonts::{self, search::Resolution};
 use crate::templates::{self, FEATURES};
 use crate::tui;
 use std::fs;
@@ -33,6 +33,16 @@ fn run_inner(args: Vec<String>) -> Result<(), String> {
         return add_font_by_name(&query);
     }
 
+    if first == "icons" {
+        let Some(set) = args.get(1) else {
+            return Err("Usage: plyx add icons material-symbols[-rounded|-sharp] [icon names...]".to_string());
+        };
+        let style = fonts::icons::Style::parse(set).ok_or_else(|| {
+            format!("Unknown icon set '{set}'. Available: material-symbols, material-symbols-rounded, material-symbols-sharp")
+        })?;
+        return fonts::icons::add(style, &args[2..], Path::new("."));
+    }
+
     // Treat as a feature key
     add_feature_by_key(&first)
 }
@@ -64,14 +74,25 @@ fn interactive_add() -> Result<(), String> {
         return Ok(());
     }
 
+    // Fail before touching Cargo.toml if the fonts can't be downloaded.
+    if !result.fonts.is_empty() {
+        fonts::require_downloadable()?;
+    }
+
     // Apply features
     if !result.features.is_empty() {
         apply_features(&result.features)?;
     }
 
-    // Download fonts
-    for font_name in &result.fonts {
-        fonts::download(font_name, Path::new("assets/fonts"))?;
+    // Download fonts; keep whichever arrived even if some failed.
+    if !result.fonts.is_empty() {
+        let families: Vec<&str> = result.fonts.iter().map(String::as_str).collect();
+        let downloads = fonts::download_all(&families, Path::new("assets/fonts"))?;
+        for font in &downloads.done {
+            fonts::lock::record_download(Path::new("."), font)?;
+        }
+        fonts::codegen::regenerate(Path::new("."))?;
+        downloads.check()?;
     }
 
     println!("\nDone!");
@@ -107,23 +128,35 @@ fn add_feature_by_key(key: &str) -> Result<(), String> {
 // ── Non-interactive font add ────────────────────────────────────────────
 
 fn add_font_by_name(query: &str) -> Result<(), String> {
+    fonts::require_downloadable()?;
     let font_list = fonts::load_font_list()?;
-    let results = fonts::search(&font_list, query);
 
-    if results.is_empty() {
-        return Err(format!("No font found matching '{query}'."));
-    }
-
-    let best = results[0];
+    let best = match fonts::resolve(&font_list, query) {
+        Resolution::Found(font) => font.family.as_str(),
+        Resolution::Ambiguous(candidates) => {
+            let list: Vec<String> = candidates
+                .iter()
+                .map(|f| format!("  {}", f.family))
+                .collect();
+            return Err(format!(
+                "'{query}' matches several fonts:\n{}\n\
+                 Run `plyx add font <exact name>` with one of these.",
+                list.join("\n")
+            ));
+        }
+        Resolution::NotFound => {
+            return Err(format!("No font found matching '{query}'."));
+        }
+    };
 
-    // Check if already installed
-    let installed = detect_installed_fonts();
-    if installed.iter().any(|f| f.eq_ignore_ascii_case(best)) {
+    if fonts::metadata::is_installed(Path::new("assets/fonts"), best) {
         println!("Font '{best}' is already installed.");
         return Ok(());
     }
 
-    fonts::download(best, Path::new("assets/fonts"))?;
+    let font = fonts::download(best, Path::new("assets/fonts"))?;
+    fonts::lock::record_download(Path::new("."), &font)?;
+    fonts::codegen::regenerate(Path::new("."))?;
     println!("Added font '{best}'.");
     Ok(())
 }
@@ -131,7 +164,7 @@ fn add_font_by_name(query: &str) -> Result<(), String> {
 // ── Cargo.toml manipulation ─────────────────────────────────────────────
 
 /// Detect which ply-engine features are currently enabled in Cargo.toml.
-fn detect_enabled_features(cargo_str: &str) -> Vec<String> {
+pub(crate) fn detect_enabled_features(cargo_str: &str) -> Vec<String> {
     let doc = match cargo_str.parse::<toml_edit::DocumentMut>() {
         Ok(d) => d,
         Err(_) => return Vec::new(),
@@ -274,37 +307,10 @@ fn apply_features(new_features: &[String]) -> Result<(), String> {
     Ok(())
 }
 
-/// Detect fonts already present in assets/fonts/ (by filename → font name).
+/// Families of the fonts already in assets/fonts/, read from the font files.
 fn detect_installed_fonts() -> Vec<String> {
-    let fonts_dir = Path::new("assets/fonts");
-    if !fonts_dir.exists() {
-        return Vec::new();
-    }
-
-    let mut names = Vec::new();
-    if let Ok(entries) = fs::read_dir(fonts_dir) {
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.extension().and_then(|e| e.to_str()) == Some("ttf") {
-                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
-                    // Convert filename back to title case: "open_sans" → "Open Sans"
-                    let name = stem
-                        .split('_')
-                        .map(|word| {
-                            let mut chars = word.chars();
-                            match chars.next() {
-                                Some(c) => {
-                                    c.to_uppercase().to_string() + &chars.collect::<String>()
-                                }
-                                None => String::new(),
-                            }
-                        })
-                        .collect::<Vec<_>>()
-                        .join(" ");
-                    names.push(name);
-                }
-            }
-        }
-    }
-    names
+    fonts::metadata::installed(Path::new("assets/fonts"))
+        .iter()
+        .map(|f| f.family())
+        .collect()
 }
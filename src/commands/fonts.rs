@@ -0,0 +1,507 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fonts::lock::{self, Lock, LOCK_FILE};
+use crate::fonts::codegen::FontConst;
+use crate::fonts::{self, codegen, license, metadata, source};
+use crate::net;
+
+/// Subcommands of `plyx fonts`.
+pub enum Action {
+    List,
+    Remove { name: String, force: bool },
+    Default { name: String },
+    Sync,
+}
+
+pub fn run(action: Action) {
+    if let Err(e) = run_inner(action) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run_inner(action: Action) -> Result<(), String> {
+    if !Path::new("Cargo.toml").exists() {
+        return Err(
+            "No Cargo.toml found. Run this from the root of a ply-engine project.".to_string(),
+        );
+    }
+
+    let fonts_dir = Path::new("assets/fonts");
+    match action {
+        Action::List => list(fonts_dir),
+        Action::Remove { name, force } => remove(fonts_dir, &name, force),
+        Action::Default { name } => set_default(fonts_dir, &name),
+        Action::Sync => sync(fonts_dir),
+    }
+}
+
+// ── Installed font inventory ────────────────────────────────────────────
+
+/// One installed font file with what plyx knows about it.
+struct Installed {
+    family: String,
+    /// Subfamily from the name table, e.g. "Bold Italic".
+    style: Option<String>,
+    version: Option<String>,
+    file: String,
+    size: u64,
+    weight: u16,
+    license: Option<String>,
+}
+
+fn inventory(fonts_dir: &Path) -> Result<Vec<Installed>, String> {
+    let records = license::read_manifest(fonts_dir)?;
+    Ok(metadata::installed(fonts_dir)
+        .into_iter()
+        .map(|font| {
+            let record = records.iter().find(|r| r.font_file == font.file);
+            Installed {
+                family: font.family(),
+                style: font.meta.as_ref().map(|m| m.subfamily.clone()),
+                version: font.meta.as_ref().and_then(|m| m.version.clone()),
+                size: fs::metadata(fonts_dir.join(&font.file)).map(|m| m.len()).unwrap_or(0),
+                weight: font
+                    .meta
+                    .as_ref()
+                    .map(|m| m.weight)
+                    .unwrap_or_else(|| fonts::weight_from_filename(&font.file)),
+                license: record.map(|r| r.license.clone()),
+                file: font.file,
+            }
+        })
+        .collect())
+}
+
+/// Find an installed font by family ("Inter"), family and style
+/// ("Inter Bold") or file name (case-insensitive).
+fn find_installed<'a>(installed: &'a [Installed], name: &str) -> Result<&'a Installed, String> {
+    let name = name.trim();
+    installed
+        .iter()
+        .find(|f| {
+            f.family.eq_ignore_ascii_case(name)
+                || f.style
+                    .as_ref()
+                    .is_some_and(|s| format!("{} {s}", f.family).eq_ignore_ascii_case(name))
+                || f.file.eq_ignore_ascii_case(name)
+                || Path::new(&f.file)
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|stem| stem.eq_ignore_ascii_case(name))
+        })
+        .ok_or_else(|| {
+            let names: Vec<&str> = installed.iter().map(|f| f.family.as_str()).collect();
+            if names.is_empty() {
+                format!("No font named '{name}' is installed (assets/fonts/ is empty).")
+            } else {
+                format!("No font named '{name}' is installed. Installed: {}", names.join(", "))
+            }
+        })
+}
+
+/// Rust files under `src/` that mention `file` (by path or generated
+/// constant), excluding the generated `src/fonts.rs`.
+fn references(file: &str) -> Vec<PathBuf> {
+    let path_lit = format!("assets/fonts/{file}");
+    let ident = format!("fonts::{}", codegen::font_const(file).ident);
+    rust_files(Path::new("src"))
+        .into_iter()
+        .filter(|p| p != Path::new("src/fonts.rs"))
+        .filter(|p| {
+            fs::read_to_string(p)
+                .map(|s| s.contains(&path_lit) || contains_ident(&s, &ident))
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+fn rust_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                files.extend(rust_files(&path));
+            } else if path.extension().and_then(|e| e.to_str()) == Some("rs") {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    files
+}
+
+/// Whole-identifier match, so `FOO_REGULAR` doesn't match `FOO_REGULAR_2`.
+fn contains_ident(haystack: &str, ident: &str) -> bool {
+    haystack.match_indices(ident).any(|(i, _)| {
+        let after = haystack[i + ident.len()..].chars().next();
+        !after.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+// ── list ────────────────────────────────────────────────────────────────
+
+fn list(fonts_dir: &Path) -> Result<(), String> {
+    let installed = inventory(fonts_dir)?;
+    if installed.is_empty() {
+        println!("No fonts installed. Add one with `plyx add font <name>`.");
+        return Ok(());
+    }
+
+    let rows: Vec<[String; 8]> = installed
+        .iter()
+        .map(|f| {
+            let refs = references(&f.file);
+            let used = if refs.is_empty() {
+                "no".to_string()
+            } else {
+                let names: Vec<String> = refs.iter().map(|p| p.display().to_string()).collect();
+                format!("yes ({})", names.join(", "))
+            };
+            [
+                f.family.clone(),
+                f.style.clone().unwrap_or_else(|| "?".to_string()),
+                f.file.clone(),
+                net::human_size(f.size),
+                f.weight.to_string(),
+                f.version.clone().unwrap_or_else(|| "?".to_string()),
+                f.license.clone().unwrap_or_else(|| "?".to_string()),
+                used,
+            ]
+        })
+        .collect();
+
+    let header = [
+        "Family", "Style", "File", "Size", "Weight", "Version", "License", "Used in src/",
+    ];
+    let mut widths = header.map(|h| h.len());
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    let print_row = |cells: &[&str]| {
+        let line: Vec<String> = cells
+            .iter()
+            .zip(widths)
+            .map(|(c, w)| format!("{c:<w$}"))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    };
+    print_row(&header);
+    for row in &rows {
+        print_row(&row.each_ref().map(|s| s.as_str()));
+    }
+    Ok(())
+}
+
+// ── remove ──────────────────────────────────────────────────────────────
+
+fn remove(fonts_dir: &Path, name: &str, force: bool) -> Result<(), String> {
+    let installed = inventory(fonts_dir)?;
+    let font = find_installed(&installed, name)?;
+
+    let refs = references(&font.file);
+    if !refs.is_empty() && !force {
+        let names: Vec<String> = refs.iter().map(|p| p.display().to_string()).collect();
+        return Err(format!(
+            "Sorry, plyx doesn't want to break anything :(\n\
+             {} is still used in {}. Remove those uses first, or pass --force.",
+            font.file,
+            names.join(", ")
+        ));
+    }
+
+    fs::remove_file(fonts_dir.join(&font.file))
+        .map_err(|e| format!("Failed to remove {}: {e}", font.file))?;
+    license::forget(&font.file, fonts_dir)?;
+    lock::forget(Path::new("."), &font.file)?;
+    codegen::regenerate(Path::new("."))?;
+
+    println!("Removed font '{}' ({}).", font.family, font.file);
+    Ok(())
+}
+
+// ── sync ────────────────────────────────────────────────────────────────
+
+/// Restore missing files listed in `plyx.lock` and check the rest.
+fn sync(fonts_dir: &Path) -> Result<(), String> {
+    let lock = Lock::read(Path::new("."))?;
+    if lock.fonts.is_empty() && lock.licenses.is_empty() {
+        println!("Nothing to sync: {LOCK_FILE} lists no fonts.");
+        return Ok(());
+    }
+
+    let assets = lock
+        .fonts
+        .iter()
+        .map(|f| (&f.file, &f.url, &f.sha256))
+        .chain(lock.licenses.iter().map(|l| (&l.file, &l.url, &l.sha256)));
+
+    let mut restored = 0;
+    let mut problems = Vec::new();
+    for (file, url, sha256) in assets {
+        let path = Path::new(file);
+        if path.exists() {
+            let data = fs::read(path).map_err(|e| format!("Failed to read {file}: {e}"))?;
+            let actual = lock::sha256_hex(&data);
+            if actual != *sha256 {
+                problems.push(format!("{file}: local file doesn't match {LOCK_FILE} (sha256 {actual})"));
+            }
+            continue;
+        }
+
+        println!("Restoring {file}...");
+        let data = match source::fetch(url, source::MAX_FONT_BYTES) {
+            Ok(data) => data,
+            Err(e) => {
+                problems.push(format!("{file}: download failed: {e}"));
+                continue;
+            }
+        };
+        let actual = lock::sha256_hex(&data);
+        if actual != *sha256 {
+            problems.push(format!(
+                "{file}: {url} now serves different content (sha256 {actual}); not written"
+            ));
+            continue;
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+        }
+        fs::write(path, &data).map_err(|e| format!("Failed to write {file}: {e}"))?;
+        restored += 1;
+    }
+
+    // Rebuild license manifest entries for licenses that are back on disk.
+    let manifest = license::read_manifest(fonts_dir)?;
+    for l in &lock.licenses {
+        if Path::new(&l.file).exists() && !manifest.iter().any(|r| r.font_file == l.font_file) {
+            license::record_entry(fonts_dir, &l.to_record())?;
+        }
+    }
+    if restored > 0 {
+        codegen::regenerate(Path::new("."))?;
+    }
+
+    let total = lock.fonts.len() + lock.licenses.len();
+    if problems.is_empty() {
+        println!("All {total} locked files present ({restored} restored).");
+        Ok(())
+    } else {
+        Err(format!(
+            "{} of {total} locked files have problems:\n  {}",
+            problems.len(),
+            problems.join("\n  ")
+        ))
+    }
+}
+
+// ── default ─────────────────────────────────────────────────────────────
+
+fn set_default(fonts_dir: &Path, name: &str) -> Result<(), String> {
+    let installed = inventory(fonts_dir)?;
+    let font = find_installed(&installed, name)?;
+
+    let main_path = Path::new("src/main.rs");
+    let src = fs::read_to_string(main_path)
+        .map_err(|e| format!("Failed to read src/main.rs: {e}"))?;
+    let Some(constant) = codegen::installed_consts(Path::new("."))
+        .into_iter()
+        .find(|c| c.path == format!("assets/fonts/{}", font.file))
+    else {
+        return Err(format!("'{}' isn't in assets/fonts.", font.family));
+    };
+    let updated = rewrite_default_font(&src, &constant)?;
+
+    if updated == src {
+        println!("'{}' is already the default font.", font.family);
+        return Ok(());
+    }
+    fs::write(main_path, updated).map_err(|e| format!("Failed to write src/main.rs: {e}"))?;
+    println!("Default font is now '{}' ({}).", font.family, constant.path);
+    Ok(())
+}
+
+/// Point `DEFAULT_FONT` at `font`: the constant in `DEFAULT_FONT … =
+/// &fonts::…`, or the string literal in the older `DEFAULT_FONT … =
+/// FontAsset::Path("…")`.
+///
+/// Works on tokens rather than text, so comments, other string literals and
+/// formatting are left exactly as they were.
+fn rewrite_default_font(src: &str, font: &FontConst) -> Result<String, String> {
+    let tokens = tokenize(src);
+
+    for (i, tok) in tokens.iter().enumerate() {
+        if *tok != Token::Ident("DEFAULT_FONT") {
+            continue;
+        }
+        let stmt = tokens[i + 1..]
+            .iter()
+            .take_while(|t| **t != Token::Punct(';'));
+        let stmt: Vec<&Token> = stmt.collect();
+        let module = [Token::Ident("fonts"), Token::Punct(':'), Token::Punct(':')];
+        for w in stmt.windows(module.len() + 1) {
+            if w[..module.len()].iter().zip(&module).all(|(a, b)| *a == b) {
+                if let Token::Ident(old) = *w[module.len()] {
+                    // Identifiers are slices of `src`.
+                    let start = old.as_ptr() as usize - src.as_ptr() as usize;
+                    let end = start + old.len();
+                    return Ok(format!("{}{}{}", &src[..start], font.ident, &src[end..]));
+                }
+            }
+        }
+        let pattern = [
+            Token::Ident("FontAsset"),
+            Token::Punct(':'),
+            Token::Punct(':'),
+            Token::Ident("Path"),
+            Token::Punct('('),
+        ];
+        for w in stmt.windows(pattern.len() + 1) {
+            if w[..pattern.len()].iter().zip(&pattern).all(|(a, b)| *a == b) {
+                if let Token::Str(start, end) = *w[pattern.len()] {
+                    let literal = format!("{:?}", font.path);
+                    return Ok(format!("{}{literal}{}", &src[..start], &src[end..]));
+                }
+            }
+        }
+    }
+
+    Err("Couldn't find `DEFAULT_FONT: &FontAsset = &fonts::…` in src/main.rs.".to_string())
+}
+
+/// Just enough of a Rust lexer to find items outside comments and strings.
+#[derive(Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Punct(char),
+    /// A string literal's byte range, quotes included.
+    Str(usize, usize),
+    Other,
+}
+
+fn tokenize(src: &str) -> Vec<Token<'_>> {
+    let bytes = src.as_bytes();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let c = bytes[i];
+        let rest = &src[i..];
+
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if rest.starts_with("//") {
+            i += rest.find('\n').unwrap_or(rest.len());
+        } else if rest.starts_with("/*") {
+            // Block comments nest in Rust.
+            let mut depth = 0;
+            while i < bytes.len() {
+                if src[i..].starts_with("/*") {
+                    depth += 1;
+                    i += 2;
+                } else if src[i..].starts_with("*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
+                    }
+                } else {
+                    i += 1;
+                }
+            }
+        } else if let Some(len) = raw_string_len(rest) {
+            tokens.push(Token::Str(i, i + len));
+            i += len;
+        } else if c == b'"' || (c == b'b' && rest[1..].starts_with('"')) {
+            let start = i;
+            i += if c == b'b' { 2 } else { 1 };
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i = (i + 1).min(bytes.len());
+            tokens.push(Token::Str(start, i));
+        } else if c == b'\'' {
+            // Char literal ('a', '\n') or lifetime ('a).
+            if rest[1..].starts_with('\\') {
+                i += 2;
+                while i < bytes.len() && bytes[i] != b'\'' {
+                    i += 1;
+                }
+                i += 1;
+            } else {
+                let ch_len = rest[1..].chars().next().map_or(0, char::len_utf8);
+                if rest[1 + ch_len..].starts_with('\'') {
+                    i += 2 + ch_len;
+                } else {
+                    i += 1;
+                }
+            }
+            tokens.push(Token::Other);
+        } else if c == b'_' || c.is_ascii_alphabetic() {
+            let len = rest
+                .find(|ch: char| !(ch == '_' || ch.is_ascii_alphanumeric()))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Ident(&rest[..len]));
+            i += len;
+        } else if c.is_ascii_digit() {
+            let len = rest
+                .find(|ch: char| !(ch == '_' || ch == '.' || ch.is_ascii_alphanumeric()))
+                .unwrap_or(rest.len());
+            tokens.push(Token::Other);
+            i += len;
+        } else {
+            let ch = rest.chars().next().unwrap_or(' ');
+            tokens.push(Token::Punct(ch));
+            i += ch.len_utf8();
+        }
+    }
+    tokens
+}
+
+/// Length of a raw (byte) string literal at the start of `s`: `r"…"`,
+/// `r#"…"#`, `br##"…"##`, ….
+fn raw_string_len(s: &str) -> Option<usize> {
+    let after_prefix = s.strip_prefix("br").or_else(|| s.strip_prefix('r'))?;
+    let hashes = after_prefix.len() - after_prefix.trim_start_matches('#').len();
+    let body = after_prefix[hashes..].strip_prefix('"')?;
+    let closing = format!("\"{}", "#".repeat(hashes));
+    let end = body.find(&closing)?;
+    Some(s.len() - body.len() + end + closing.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_default_font() {
+        let src = r##"
+// static DEFAULT_FONT: FontAsset = FontAsset::Path("assets/fonts/comment.ttf");
+const NOTE: &str = r#"DEFAULT_FONT = FontAsset::Path("x")"#;
+async fn main() {
+    static DEFAULT_FONT: FontAsset = FontAsset::Path( "assets/fonts/lexend.ttf" );
+    let mut ply = Ply::<()>::new(&DEFAULT_FONT).await;
+}
+"##;
+        let inter = codegen::font_const("inter.ttf");
+        let out = rewrite_default_font(src, &inter).unwrap();
+        assert_eq!(
+            out,
+            src.replace("\"assets/fonts/lexend.ttf\"", "\"assets/fonts/inter.ttf\"")
+        );
+
+        let src = "// DEFAULT_FONT = &fonts::OLD;\nstatic DEFAULT_FONT: &FontAsset = &fonts::LEXEND_REGULAR;\n";
+        assert_eq!(
+            rewrite_default_font(src, &inter).unwrap(),
+            src.replace("&fonts::LEXEND_REGULAR", "&fonts::INTER_REGULAR")
+        );
+
+        assert!(rewrite_default_font("fn main() {}", &inter).is_err());
+    }
+}
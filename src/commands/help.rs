@@ -8,6 +8,8 @@ pub fn run() {
     println!("  add          Add features or fonts to an existing project");
     println!("  apk          Build an Android APK");
     println!("  web          Build for web (WASM)");
+    println!("  fonts        List, remove, rename or set the default font");
+    println!("  licenses     Write THIRD_PARTY_NOTICES for bundled fonts");
     println!("  completions  Generate shell completions");
     println!();
     println!("Run `plyx <command> --help` for more info on a command.");
@@ -12,6 +12,11 @@ pub fn run() {
 }
 
 fn run_inner() -> Result<(), String> {
+    // Every new project starts with a downloaded font.
+    fonts::require_downloadable()?;
+
+    // Fetch the catalog while the user types the project name.
+    let font_list = fonts::spawn_font_list();
     let name = tui::text_input("Project name:", "my-app")?;
 
     let project_dir = Path::new(&name);
@@ -20,7 +25,7 @@ fn run_inner() -> Result<(), String> {
     }
 
     // Font selection
-    let font_list = fonts::load_font_list()?;
+    let font_list = font_list.wait()?;
 
     let mut options: Vec<String> = Vec::new();
     for &suggested in fonts::SUGGESTED_FONTS {
@@ -34,15 +39,16 @@ fn run_inner() -> Result<(), String> {
     for font in &font_list {
         if !fonts::SUGGESTED_FONTS
             .iter()
-            .any(|&s| s.eq_ignore_ascii_case(font))
+            .any(|&s| s.eq_ignore_ascii_case(&font.family))
         {
-            options.push(font.clone());
+            options.push(font.family.clone());
         }
     }
 
     let selected_label = tui::search_select(
         "Choose your first font:",
         &options,
+        &font_list,
         "Don't worry, you can add more fonts later with `plyx add`",
     )?;
 
@@ -54,7 +60,7 @@ fn run_inner() -> Result<(), String> {
         font_name.to_string()
     } else {
         fonts::find_by_name(&font_list, font_name)
-            .map(|s| s.to_string())
+            .map(|f| f.family.clone())
             .unwrap_or_else(|| font_name.to_string())
     };
 
@@ -83,17 +89,20 @@ fn run_inner() -> Result<(), String> {
             .map_err(|e| format!("Failed to create shaders/: {e}"))?;
     }
 
-    fonts::download(&resolved_font, &project_dir.join("assets/fonts"))?;
-    let font_filename = resolved_font.to_lowercase().replace(' ', "_") + ".ttf";
+    let font = fonts::download(&resolved_font, &project_dir.join("assets/fonts"))?;
+    fonts::lock::record_download(project_dir, &font)?;
+    let font_filename = fonts::font_filename(&resolved_font);
 
     let cargo_toml = generate_cargo_toml(&name, &enabled_refs);
     fs::write(project_dir.join("Cargo.toml"), cargo_toml)
         .map_err(|e| format!("Failed to write Cargo.toml: {e}"))?;
 
-    let main_rs = generate_main_rs(&font_filename);
+    let main_rs = generate_main_rs(&fonts::codegen::font_const(&font_filename).ident);
     fs::write(project_dir.join("src/main.rs"), main_rs)
         .map_err(|e| format!("Failed to write src/main.rs: {e}"))?;
 
+    fonts::codegen::regenerate(project_dir)?;
+
     if has_shader_pipeline {
         fs::write(project_dir.join("build.rs"), BUILD_RS)
             .map_err(|e| format!("Failed to write build.rs: {e}"))?;
@@ -2,6 +2,7 @@ use std::fs;
 use std::path::Path;
 use std::process::Command;
 
+use crate::net;
 use crate::templates;
 use crate::tui;
 
@@ -66,7 +67,7 @@ fn build_simulator(crate_name: &str, _auto: bool) -> Result<(), String> {
     // 1. cargo build
     println!("Building for {target} (release)...");
     let status = Command::new("cargo")
-        .args(["build", "--release", "--target", target])
+        .args(cargo_build_args(target))
         .status()
         .map_err(|e| format!("Failed to run cargo: {e}"))?;
     if !status.success() {
@@ -92,7 +93,7 @@ fn build_simulator(crate_name: &str, _auto: bool) -> Result<(), String> {
     }
 
     // 5. Launch
-    let bundle_id = format!("com.{}", crate_name.replace('-', "-"));
+    let bundle_id = format!("com.{}", crate_name);
     println!("Launching {bundle_id} in simulator...");
     let status = Command::new("xcrun")
         .args(["simctl", "launch", "booted", &bundle_id])
@@ -202,7 +203,7 @@ fn build_device(crate_name: &str, auto: bool) -> Result<(), String> {
     // 1. cargo build
     println!("Building for {target} (release)...");
     let status = Command::new("cargo")
-        .args(["build", "--release", "--target", target])
+        .args(cargo_build_args(target))
         .status()
         .map_err(|e| format!("Failed to run cargo: {e}"))?;
     if !status.success() {
@@ -359,6 +360,7 @@ fn ensure_ios_deploy(auto: bool) -> Result<(), String> {
         );
     }
 
+    net::require_online("Installing ios-deploy")?;
     println!("Installing ios-deploy...");
     let status = Command::new("brew")
         .args(["install", "ios-deploy"])
@@ -371,6 +373,15 @@ fn ensure_ios_deploy(auto: bool) -> Result<(), String> {
     Ok(())
 }
 
+/// `cargo build` arguments for a release build of `target`.
+fn cargo_build_args(target: &str) -> Vec<&str> {
+    let mut args = vec!["build", "--release", "--target", target];
+    if net::is_offline() {
+        args.push("--offline");
+    }
+    args
+}
+
 /// Ensure the given Rust target is installed, adding it silently if needed.
 fn ensure_rust_target(target: &str) -> Result<(), String> {
     let output = Command::new("rustup")
@@ -382,6 +393,7 @@ fn ensure_rust_target(target: &str) -> Result<(), String> {
         return Ok(());
     }
 
+    net::require_online(&format!("Installing Rust target {target}"))?;
     println!("Installing Rust target {target}...");
     let status = Command::new("rustup")
         .args(["target", "add", target])
@@ -432,7 +444,7 @@ fn create_app_bundle(crate_name: &str, target: &str, app_path: &Path, target_dir
     // Generate Info.plist (don't overwrite)
     let plist_path = app_path.join("Info.plist");
     if !plist_path.exists() {
-        let bundle_id = format!("com.{}", crate_name.replace('-', "-"));
+        let bundle_id = format!("com.{}", crate_name);
         let display_name = crate_name
             .split('-')
             .map(|w| {
@@ -456,35 +468,13 @@ fn create_app_bundle(crate_name: &str, target: &str, app_path: &Path, target_dir
     let assets_src = Path::new("assets");
     let assets_dst = app_path.join("assets");
     if assets_src.exists() {
-        copy_dir_recursive(assets_src, &assets_dst)?;
+        super::web::copy_dir_recursive(assets_src, &assets_dst)?;
         println!("  Copied assets/");
     }
 
     Ok(())
 }
 
-/// Recursively copy a directory.
-fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
-    fs::create_dir_all(dst).map_err(|e| format!("Failed to create {}: {e}", dst.display()))?;
-
-    let entries =
-        fs::read_dir(src).map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
-
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
-        let src_path = entry.path();
-        let dst_path = dst.join(entry.file_name());
-
-        if src_path.is_dir() {
-            copy_dir_recursive(&src_path, &dst_path)?;
-        } else {
-            fs::copy(&src_path, &dst_path)
-                .map_err(|e| format!("Failed to copy {}: {e}", src_path.display()))?;
-        }
-    }
-    Ok(())
-}
-
 fn generate_actions_workflow(crate_name: &str) -> Result<(), String> {
     let workflow_dir = Path::new(".github/workflows");
     let workflow_path = workflow_dir.join("ios.yml");
@@ -0,0 +1,93 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::fonts::{self, license, lock, metadata::FontMeta, FontSource};
+use crate::net;
+
+/// Default notices location. Under `assets/`, so every build target ships it.
+const DEFAULT_OUT: &str = "assets/THIRD_PARTY_NOTICES";
+
+pub fn run(out: Option<PathBuf>) {
+    if let Err(e) = run_inner(out) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run_inner(out: Option<PathBuf>) -> Result<(), String> {
+    if !Path::new("Cargo.toml").exists() {
+        return Err(
+            "No Cargo.toml found. Run this from the root of a ply-engine project.".to_string(),
+        );
+    }
+
+    let fonts_dir = Path::new("assets/fonts");
+    fetch_missing_licenses(fonts_dir)?;
+
+    // Only fonts that are still installed.
+    let records: Vec<license::LicenseRecord> = license::read_manifest(fonts_dir)?
+        .into_iter()
+        .filter(|r| fonts_dir.join(&r.font_file).exists())
+        .collect();
+
+    if records.is_empty() {
+        return Err("No font licenses recorded in assets/fonts/licenses/.".to_string());
+    }
+
+    let notices = license::render_notices(fonts_dir, &records)?;
+    let out = out.unwrap_or_else(|| PathBuf::from(DEFAULT_OUT));
+    if let Some(parent) = out.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display()))?;
+    }
+    fs::write(&out, notices).map_err(|e| format!("Failed to write {}: {e}", out.display()))?;
+
+    for r in &records {
+        println!("  {} — {}", r.family, r.license);
+    }
+    let noun = if records.len() == 1 { "font" } else { "fonts" };
+    println!("\nWrote {} ({} {noun})", out.display(), records.len());
+    Ok(())
+}
+
+/// Fetch licenses for fonts that were added before plyx recorded them.
+fn fetch_missing_licenses(fonts_dir: &Path) -> Result<(), String> {
+    let recorded = license::read_manifest(fonts_dir)?;
+    let missing: Vec<String> = fs::read_dir(fonts_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .filter(|name| name.ends_with(".ttf"))
+                .filter(|name| !recorded.iter().any(|r| &r.font_file == name))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let source = FontSource::configured()?;
+    if source.is_remote() && net::is_offline() {
+        for file in &missing {
+            eprintln!("Warning: No license recorded for {file} (offline, not fetching).");
+        }
+        return Ok(());
+    }
+
+    for file in &missing {
+        let family = match FontMeta::read(&fonts_dir.join(file)) {
+            Ok(meta) => meta.family,
+            Err(_) => fonts::family_from_filename(file),
+        };
+        match license::fetch_and_record(&source, &family, file, fonts_dir) {
+            Ok(r) => {
+                println!("  Fetched {} license for {family}", r.license);
+                lock::record_license(Path::new("."), &r)?;
+            }
+            Err(e) => eprintln!("Warning: {e}"),
+        }
+    }
+    Ok(())
+}
@@ -2,9 +2,11 @@ pub mod add;
 pub mod apk;
 pub mod completions;
 pub mod easter_egg;
+pub mod fonts;
 pub mod help;
 pub mod init;
 pub mod ios;
+pub mod licenses;
 pub mod web;
 
 use std::fs;
@@ -0,0 +1,132 @@
+//! Picking the `ply_bundle.js` that matches the project's ply-engine.
+//!
+//! The JS half of the engine has to match the wasm half, so the bundle is
+//! chosen by the ply-engine version locked in `Cargo.lock` (major.minor)
+//! from the bundles embedded in plyx. A `ply_bundle.js` in the project root
+//! is used instead, if there is one.
+//!
+//! Any other version is an error rather than a page that fails at runtime
+//! with missing imports.
+
+use std::borrow::Cow;
+use std::fs;
+use std::path::Path;
+
+/// A project's own bundle, used instead of a matching one.
+pub const OVERRIDE: &str = "ply_bundle.js";
+
+/// Bundles shipped with plyx, by ply-engine major.minor, oldest first.
+const EMBEDDED: &[(&str, &[u8])] = &[("1.1", include_bytes!("../../../ply_bundle_1.1.js"))];
+
+/// The bundle to ship, and where it came from (for the build output).
+pub struct Bundle {
+    pub data: Cow<'static, [u8]>,
+    pub origin: String,
+}
+
+/// Find the bundle for the project in the current directory.
+pub fn select() -> Result<Bundle, String> {
+    if Path::new(OVERRIDE).is_file() {
+        let data = fs::read(OVERRIDE).map_err(|e| format!("Failed to read {OVERRIDE}: {e}"))?;
+        return Ok(Bundle { data: Cow::Owned(data), origin: "project override".to_string() });
+    }
+
+    let Some(version) = locked_version()? else {
+        let (series, data) = EMBEDDED[EMBEDDED.len() - 1];
+        eprintln!(
+            "Warning: ply-engine isn't in Cargo.lock; using the bundle for ply-engine {series}."
+        );
+        return Ok(Bundle { data: Cow::Borrowed(data), origin: format!("ply-engine {series}") });
+    };
+    let series = series(&version);
+    let origin = format!("ply-engine {version}");
+
+    if let Some((_, data)) = EMBEDDED.iter().find(|(s, _)| *s == series) {
+        return Ok(Bundle { data: Cow::Borrowed(data), origin });
+    }
+
+    let supported: Vec<&str> = EMBEDDED.iter().map(|(s, _)| *s).collect();
+    let hint = if is_newer(&series, supported[supported.len() - 1]) {
+        "Update plyx (`cargo install plyx`)"
+    } else {
+        "Update ply-engine (`cargo update -p ply-engine`)"
+    };
+    Err(format!(
+        "Cargo.lock has ply-engine {version}, but this plyx only has the JS bundle for \
+         ply-engine {}. {hint}, or put the matching ply_bundle.js in the project root.",
+        supported.join(", ")
+    ))
+}
+
+/// The ply-engine version in the nearest `Cargo.lock`, if any.
+fn locked_version() -> Result<Option<String>, String> {
+    let cwd = std::env::current_dir().map_err(|e| format!("Failed to read current dir: {e}"))?;
+    let Some(lock) = cwd.ancestors().map(|d| d.join("Cargo.lock")).find(|p| p.is_file()) else {
+        return Ok(None);
+    };
+    let text = fs::read_to_string(&lock).map_err(|e| format!("Failed to read Cargo.lock: {e}"))?;
+    let mut versions = locked_versions(&text)?;
+    if versions.len() > 1 {
+        versions.sort_by(|a, b| compare(a, b));
+        eprintln!(
+            "Warning: Cargo.lock has several ply-engine versions ({}); matching the newest.",
+            versions.join(", ")
+        );
+    }
+    Ok(versions.pop())
+}
+
+fn locked_versions(lock: &str) -> Result<Vec<String>, String> {
+    let doc: toml_edit::DocumentMut =
+        lock.parse().map_err(|e| format!("Failed to parse Cargo.lock: {e}"))?;
+    let Some(packages) = doc.get("package").and_then(|p| p.as_array_of_tables()) else {
+        return Ok(Vec::new());
+    };
+    Ok(packages
+        .iter()
+        .filter(|p| p.get("name").and_then(|n| n.as_str()) == Some("ply-engine"))
+        .filter_map(|p| p.get("version").and_then(|v| v.as_str()).map(str::to_string))
+        .collect())
+}
+
+/// `1.1.4` → `1.1`.
+fn series(version: &str) -> String {
+    version.split(['.', '-', '+']).take(2).collect::<Vec<_>>().join(".")
+}
+
+fn is_newer(a: &str, b: &str) -> bool {
+    compare(a, b).is_gt()
+}
+
+/// Compare dotted versions numerically, ignoring pre-release tags.
+fn compare(a: &str, b: &str) -> std::cmp::Ordering {
+    let parts = |v: &str| -> Vec<u64> {
+        v.split(['-', '+']).next().unwrap_or("").split('.').map(|p| p.parse().unwrap_or(0)).collect()
+    };
+    parts(a).cmp(&parts(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_locked_versions() {
+        let lock = r#"
+version = 4
+
+[[package]]
+name = "ply-engine"
+version = "1.1.3"
+
+[[package]]
+name = "ply-engine-macros"
+version = "0.4.0"
+"#;
+        assert_eq!(locked_versions(lock).unwrap(), ["1.1.3"]);
+        assert_eq!(series("1.1.3"), "1.1");
+        assert_eq!(series("2.0.0-beta.1"), "2.0");
+        assert!(is_newer("1.10", "1.9"));
+        assert!(!is_newer("1.0", "1.1"));
+    }
+}
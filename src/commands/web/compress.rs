@@ -0,0 +1,253 @@
+//! `plyx web --compress` — precompressed `.gz` and `.br` siblings.
+//!
+//! Static hosts (and `plyx web --serve`) can send `app.wasm.br` in place of
+//! `app.wasm` to browsers that accept it. A sibling is only kept when it
+//! saves at least [`MIN_SAVING`] percent; images, audio and other formats
+//! that are already compressed are skipped outright.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use super::output::Output;
+use crate::net::human_size;
+
+/// Content codings and their sibling extensions, most preferred first.
+pub const ENCODINGS: &[(&str, &str)] = &[("br", "br"), ("gzip", "gz")];
+
+/// Smallest saving, in percent, worth a sibling.
+const MIN_SAVING: u64 = 5;
+
+/// Siblings skipped for saving too little, so they aren't retried until
+/// their file changes.
+const SKIP_RECORD: &str = ".plyx-compress";
+
+/// Formats that are compressed already.
+const SKIPPED: &[&str] = &[
+    "gz", "br", "zip", "png", "jpg", "jpeg", "gif", "webp", "avif", "ico", "woff", "woff2", "mp3",
+    "ogg", "opus", "m4a", "mp4", "webm", "ktx2",
+];
+
+/// Write `.gz` and `.br` siblings for every compressible file in `out`.
+/// Each encoding is redone only when its sibling is older than its file;
+/// one that didn't pay off is remembered in [`SKIP_RECORD`] instead.
+pub fn compress_dir(out: &mut Output) -> Result<(), String> {
+    let files: Vec<PathBuf> = out.files().map(Path::to_path_buf).collect();
+    let record_path = out.path(SKIP_RECORD);
+    let record = read_record(&record_path);
+    let mut skipped = BTreeSet::new();
+    let (mut original, mut gzip, mut brotli, mut count, mut fresh) = (0, 0, 0, 0, 0);
+    for rel in files {
+        if is_skipped(&rel) {
+            continue;
+        }
+        let path = out.path(&rel);
+        let mut stale = Vec::new();
+        for (_, ext) in ENCODINGS {
+            let key = (ext.to_string(), rel.clone());
+            let up_to_date = if record.entries.contains(&key) {
+                is_newer(&record_path, &path)
+            } else {
+                is_newer(&sibling(&path, ext), &path)
+            };
+            if !up_to_date {
+                stale.push(*ext);
+            } else if record.entries.contains(&key) {
+                skipped.insert(key);
+            } else {
+                out.keep(sibling(&rel, ext));
+            }
+        }
+        if stale.is_empty() {
+            fresh += 1;
+            continue;
+        }
+        let data = fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let (mut gz_len, mut br_len) = (None, None);
+        for ext in stale {
+            let compressed = match ext {
+                "gz" => gzip_bytes(&data)?,
+                _ => brotli_bytes(&data)?,
+            };
+            if !write_sibling(out, &rel, ext, &data, &compressed)? {
+                skipped.insert((ext.to_string(), rel.clone()));
+            } else if ext == "gz" {
+                gz_len = Some(compressed.len());
+            } else {
+                br_len = Some(compressed.len());
+            }
+        }
+        if gz_len.is_some() || br_len.is_some() {
+            count += 1;
+            original += data.len() as u64;
+            gzip += gz_len.unwrap_or(data.len()) as u64;
+            brotli += br_len.unwrap_or(data.len()) as u64;
+        }
+    }
+    write_record(out, &record_path, &skipped)?;
+    if count > 0 {
+        println!(
+            "  Compressed {count} file{}: {} → {} gzip, {} brotli",
+            if count == 1 { "" } else { "s" },
+            human_size(original),
+            human_size(gzip),
+            human_size(brotli)
+        );
+    }
+    if fresh > 0 {
+        println!("  {fresh} compressed file{} up to date", if fresh == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+/// Whether `path` exists and is at least as new as `base`.
+fn is_newer(path: &Path, base: &Path) -> bool {
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    match (modified(path), modified(base)) {
+        (Some(t), Some(base)) => t >= base,
+        _ => false,
+    }
+}
+
+/// The `(extension, file)` pairs whose sibling was last found not worth
+/// writing. Only trusted for files older than the record itself.
+struct Record {
+    entries: BTreeSet<(String, PathBuf)>,
+}
+
+fn read_record(path: &Path) -> Record {
+    let text = fs::read_to_string(path).unwrap_or_default();
+    let entries = text
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .map(|(ext, rel)| (ext.to_string(), PathBuf::from(rel)))
+        .collect();
+    Record { entries }
+}
+
+/// Rewrite the record, so it is newer than every file it vouches for.
+fn write_record(
+    out: &mut Output,
+    path: &Path,
+    skipped: &BTreeSet<(String, PathBuf)>,
+) -> Result<(), String> {
+    if skipped.is_empty() {
+        return Ok(());
+    }
+    let text: String = skipped
+        .iter()
+        .map(|(ext, rel)| format!("{ext} {}\n", rel.display()))
+        .collect();
+    out.keep(SKIP_RECORD);
+    fs::write(path, text).map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// `path` with `.<ext>` appended, e.g. `app.wasm` → `app.wasm.br`.
+pub fn sibling(path: &Path, ext: &str) -> PathBuf {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(".");
+    name.push(ext);
+    PathBuf::from(name)
+}
+
+fn is_skipped(path: &Path) -> bool {
+    let ext = path.extension().and_then(|e| e.to_str()).unwrap_or("").to_lowercase();
+    SKIPPED.contains(&ext.as_str())
+}
+
+/// Write `compressed` next to `rel` if it is small enough to be worth it.
+/// Returns whether it was written.
+fn write_sibling(
+    out: &mut Output,
+    rel: &Path,
+    ext: &str,
+    data: &[u8],
+    compressed: &[u8],
+) -> Result<bool, String> {
+    let saving = (data.len().saturating_sub(compressed.len()) as u64) * 100 / (data.len().max(1) as u64);
+    if saving < MIN_SAVING {
+        return Ok(false);
+    }
+    // Written even when unchanged, so the sibling is newer than its file.
+    let dest = out.path(sibling(rel, ext));
+    out.keep(sibling(rel, ext));
+    fs::write(&dest, compressed).map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+    Ok(true)
+}
+
+fn gzip_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::best());
+    encoder
+        .write_all(data)
+        .and_then(|()| encoder.finish())
+        .map_err(|e| format!("Failed to gzip: {e}"))
+}
+
+fn brotli_bytes(data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    let params = brotli::enc::BrotliEncoderParams {
+        quality: 11,
+        lgwin: 22,
+        ..Default::default()
+    };
+    brotli::BrotliCompress(&mut &data[..], &mut out, &params)
+        .map_err(|e| format!("Failed to compress with brotli: {e}"))?;
+    Ok(out)
+}
+
+/// The entries of [`ENCODINGS`] an `Accept-Encoding` header allows, most
+/// preferred first.
+pub fn negotiate(accept: &str) -> impl Iterator<Item = &'static (&'static str, &'static str)> + '_ {
+    ENCODINGS.iter().filter(move |(name, _)| {
+        accept.split(',').any(|entry| {
+            let mut parts = entry.split(';').map(str::trim);
+            let coding = parts.next().unwrap_or("");
+            let refused = parts.any(|p| {
+                p.strip_prefix("q=").and_then(|q| q.parse::<f32>().ok()) == Some(0.0)
+            });
+            coding.eq_ignore_ascii_case(name) && !refused
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compress_dir() {
+        let dir = std::env::temp_dir().join(format!("plyx-compress-{}", std::process::id()));
+        let mut out = Output::open(&dir).unwrap();
+        out.write("app.wasm", "ply ".repeat(4096).as_bytes()).unwrap();
+        out.write("tiny.js", b"x").unwrap();
+        out.write("assets/logo.png", "png ".repeat(4096).as_bytes()).unwrap();
+
+        compress_dir(&mut out).unwrap();
+        let gz = fs::read(dir.join("app.wasm.gz")).unwrap();
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut flate2::read::GzDecoder::new(&gz[..]), &mut text).unwrap();
+        assert_eq!(text, "ply ".repeat(4096));
+        assert!(dir.join("app.wasm.br").is_file());
+        assert!(!dir.join("tiny.js.gz").exists());
+        assert!(!dir.join("assets/logo.png.br").exists());
+        assert!(is_newer(&dir.join("app.wasm.gz"), &dir.join("app.wasm")));
+        let record = read_record(&dir.join(SKIP_RECORD));
+        assert!(record.entries.contains(&("gz".into(), "tiny.js".into())));
+        assert_eq!(out.files().count(), 6);
+
+        // A second build leaves every sibling, and every skip, in place.
+        let mut out = Output::open(&dir).unwrap();
+        for rel in ["app.wasm", "tiny.js", "assets/logo.png"] {
+            out.keep(rel);
+        }
+        compress_dir(&mut out).unwrap();
+        assert_eq!(out.files().count(), 6);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let pick = |accept| negotiate(accept).map(|(_, ext)| *ext).collect::<Vec<_>>();
+        assert_eq!(pick("gzip, deflate, br"), ["br", "gz"]);
+        assert_eq!(pick("gzip;q=1.0, br;q=0"), ["gz"]);
+        assert!(pick("identity").is_empty());
+    }
+}
@@ -0,0 +1,86 @@
+//! `plyx web --hash` — content-hashed filenames for cache busting.
+//!
+//! `app.wasm`, `ply_bundle.js` and the plugins are written as e.g.
+//! `app.3f9a1c.wasm`, the copied `index.html` is rewritten to match, and
+//! `asset-manifest.json` maps each logical name to its hashed one for
+//! deploy scripts. Hosts can then cache those files forever. Assets keep
+//! their names, since the app loads them by path.
+
+use std::collections::BTreeMap;
+
+use sha2::{Digest, Sha256};
+
+use super::output::Output;
+
+pub const MANIFEST: &str = "asset-manifest.json";
+
+/// Hex digits of the hash in a file name.
+const HASH_LEN: usize = 6;
+
+/// `app.wasm` → `app.<hash>.wasm`, hashing `data`.
+pub fn hashed_name(name: &str, data: &[u8]) -> String {
+    let hash: String = Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect();
+    let hash = &hash[..HASH_LEN];
+    match name.rsplit_once('.') {
+        Some((stem, ext)) => format!("{stem}.{hash}.{ext}"),
+        None => format!("{name}.{hash}"),
+    }
+}
+
+/// Point `html` at the hashed names, warning about any it never mentions.
+pub fn rewrite_all(html: &str, names: &BTreeMap<String, String>) -> String {
+    let mut html = html.to_string();
+    for (name, hashed) in names {
+        let rewritten = rewrite(&html, name, hashed);
+        if rewritten == html {
+            eprintln!(
+                "Warning: index.html doesn't mention \"{name}\"; load it as \"{hashed}\" \
+                 (see {MANIFEST})."
+            );
+        }
+        html = rewritten;
+    }
+    html
+}
+
+/// Write `asset-manifest.json`.
+pub fn write_manifest(out: &mut Output, names: &BTreeMap<String, String>) -> Result<(), String> {
+    let json = serde_json::to_string_pretty(names)
+        .map_err(|e| format!("Failed to encode {MANIFEST}: {e}"))?;
+    out.write(MANIFEST, (json + "\n").as_bytes())?;
+    println!("  Hashed {}", names.values().cloned().collect::<Vec<_>>().join(", "));
+    Ok(())
+}
+
+/// Replace quoted references to `name` (`"app.wasm"`, `'./app.wasm'`) in
+/// `html`.
+fn rewrite(html: &str, name: &str, hashed: &str) -> String {
+    let mut html = html.to_string();
+    for quote in ['"', '\'', '`'] {
+        for prefix in ["", "./"] {
+            html = html.replace(
+                &format!("{quote}{prefix}{name}{quote}"),
+                &format!("{quote}{prefix}{hashed}{quote}"),
+            );
+        }
+    }
+    html
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hashed_names() {
+        assert_eq!(hashed_name("app.wasm", b"ply"), "app.c7a416.wasm");
+
+        let html = "<script src=\"./ply_bundle.js\"></script>\n<script>load('app.wasm');</script>";
+        assert_eq!(
+            rewrite(html, "app.wasm", "app.3f9a1c.wasm"),
+            "<script src=\"./ply_bundle.js\"></script>\n<script>load('app.3f9a1c.wasm');</script>"
+        );
+        assert!(rewrite(html, "ply_bundle.js", "ply_bundle.abcdef.js")
+            .contains("src=\"./ply_bundle.abcdef.js\""));
+    }
+}
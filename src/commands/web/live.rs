@@ -0,0 +1,153 @@
+//! Live reload for `plyx web --watch`.
+//!
+//! Pages served in watch mode get a small script that listens on a
+//! server-sent event stream. After a successful rebuild the server sends
+//! `reload`; after a failed one it sends `build-error` with cargo's output,
+//! which the script shows as an overlay until the next successful build.
+
+use std::io::Write;
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+
+use tiny_http::{Request, Response};
+
+/// Where the injected script is served from.
+pub const SCRIPT_PATH: &str = "/__plyx/live.js";
+
+/// The event stream the script listens on.
+pub const EVENTS_PATH: &str = "/__plyx/events";
+
+/// How often an idle stream gets a keep-alive comment, which also notices
+/// closed tabs.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub const SCRIPT: &str = r#"(() => {
+  const events = new EventSource("/__plyx/events");
+  events.addEventListener("reload", () => location.reload());
+  events.addEventListener("build-error", (e) => {
+    let overlay = document.getElementById("__plyx_overlay");
+    if (!overlay) {
+      overlay = document.createElement("pre");
+      overlay.id = "__plyx_overlay";
+      overlay.style.cssText =
+        "position:fixed;inset:0;margin:0;padding:24px;overflow:auto;z-index:2147483647;" +
+        "background:rgba(24,0,0,0.92);color:#ffb4b4;font:13px/1.5 ui-monospace,monospace;" +
+        "white-space:pre-wrap";
+      document.body.appendChild(overlay);
+    }
+    overlay.textContent = JSON.parse(e.data);
+  });
+})();
+"#;
+
+/// Shown in place of `index.html` while no build has succeeded yet.
+pub const WAITING_PAGE: &str = "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\">\
+<title>plyx</title></head><body style=\"background:black;color:#ccc;font-family:sans-serif\">\
+<p>Waiting for the first successful build…</p></body></html>\n";
+
+/// The latest build result, shared with every open page.
+#[derive(Default)]
+pub struct LiveReload {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    /// Bumped on every build.
+    generation: u64,
+    /// Cargo's errors, if the latest build failed.
+    error: Option<String>,
+}
+
+impl LiveReload {
+    /// The build succeeded: reload every page.
+    pub fn reload(&self) {
+        self.update(None);
+    }
+
+    /// The build failed: show `error` on every page.
+    pub fn fail(&self, error: String) {
+        self.update(Some(error));
+    }
+
+    /// Whether the latest build failed.
+    pub fn is_failing(&self) -> bool {
+        self.state.lock().unwrap_or_else(|e| e.into_inner()).error.is_some()
+    }
+
+    fn update(&self, error: Option<String>) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.generation += 1;
+        state.error = error;
+        self.changed.notify_all();
+    }
+
+    /// Answer an [`EVENTS_PATH`] request, holding the connection open and
+    /// sending an event after every build.
+    pub fn stream(&self, request: Request) {
+        let response = Response::empty(200)
+            .with_header(super::serve::header("Content-Type", "text/event-stream"))
+            .with_header(super::serve::header("Cache-Control", "no-cache"));
+        // Upgrading hands us the raw connection, so events aren't held back
+        // in a chunk buffer; the body simply runs until the page goes away.
+        let mut stream = request.upgrade("plyx-live-reload", response);
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        // A page loaded while the build is broken shows the error right away.
+        let mut pending = state.error.as_deref().map(error_event);
+        let mut seen = state.generation;
+        loop {
+            if let Some(event) = pending.take() {
+                drop(state);
+                if stream.write_all(event.as_bytes()).and_then(|()| stream.flush()).is_err() {
+                    return;
+                }
+                state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            }
+            let (next, timeout) = self
+                .changed
+                .wait_timeout_while(state, KEEP_ALIVE, |s| s.generation == seen)
+                .unwrap_or_else(|e| e.into_inner());
+            state = next;
+            pending = Some(if timeout.timed_out() {
+                ": keep-alive\n\n".to_string()
+            } else {
+                seen = state.generation;
+                match &state.error {
+                    Some(error) => error_event(error),
+                    None => "event: reload\ndata: \n\n".to_string(),
+                }
+            });
+        }
+    }
+}
+
+fn error_event(error: &str) -> String {
+    let data = serde_json::to_string(error).unwrap_or_default();
+    format!("event: build-error\ndata: {data}\n\n")
+}
+
+/// Add the live-reload script to an HTML page.
+pub fn inject(html: &str) -> String {
+    let tag = format!("<script src=\"{SCRIPT_PATH}\"></script>\n");
+    match html.rfind("</body>") {
+        Some(i) => format!("{}{tag}{}", &html[..i], &html[i..]),
+        None => format!("{html}{tag}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject() {
+        let html = inject("<body>\n<canvas></canvas>\n</body>\n</html>");
+        assert!(html.contains("</canvas>\n<script src=\"/__plyx/live.js\"></script>\n</body>"));
+        assert_eq!(
+            error_event("line 1\nline \"2\""),
+            "event: build-error\ndata: \"line 1\\nline \\\"2\\\"\"\n\n"
+        );
+    }
+}
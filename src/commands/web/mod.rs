@@ -0,0 +1,379 @@
+mod bundle;
+mod compress;
+mod fingerprint;
+mod live;
+mod optimize;
+mod output;
+mod page;
+mod plugins;
+mod pwa;
+mod serve;
+mod single_file;
+mod watch;
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::net;
+use crate::templates;
+use output::Output;
+
+pub use output::DEFAULT_DIR as DEFAULT_OUT_DIR;
+pub use serve::DEFAULT_PORT;
+
+/// Flags of `plyx web`.
+pub struct Options {
+    /// Non-interactive CI mode. Nothing in the web build prompts yet.
+    #[allow(dead_code)]
+    pub auto: bool,
+    /// Serve the output directory after building.
+    pub serve: bool,
+    /// Rebuild on changes and reload connected browsers (implies `serve`).
+    pub watch: bool,
+    /// Apply the size-optimised release profile and `wasm-opt -Oz`.
+    pub size: bool,
+    /// Write `.gz` and `.br` siblings of compressible files.
+    pub compress: bool,
+    /// Write a web app manifest and an offline service worker.
+    pub pwa: bool,
+    /// Give `app.wasm` and `ply_bundle.js` content-hashed names.
+    pub hash: bool,
+    /// Also write the whole build as one self-contained HTML file.
+    pub single_file: bool,
+    /// Empty the output directory once cargo has succeeded, before the
+    /// build's files are written.
+    pub clean: bool,
+    /// Where the build goes (`build/web` by default).
+    pub out_dir: PathBuf,
+    pub host: String,
+    pub port: u16,
+}
+
+pub fn run(opts: Options) {
+    if let Err(e) = run_inner(&opts) {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    }
+}
+
+fn run_inner(opts: &Options) -> Result<(), String> {
+    // Must be in a project root with Cargo.toml
+    if !Path::new("Cargo.toml").exists() {
+        return Err(
+            "No Cargo.toml found. Run this from the root of a ply-engine project.".to_string(),
+        );
+    }
+
+    if opts.size {
+        optimize::apply_size_profile()?;
+    }
+    let project = Project {
+        crate_name: super::read_crate_name()?,
+        target_dir: super::target_directory()?,
+        wasm_opt: optimize::wasm_opt_level(opts.size)?,
+        compress: opts.compress,
+        pwa: opts.pwa,
+        // A cache-first worker would hide every rebuild.
+        service_worker: opts.pwa && !opts.watch,
+        // The dev server turns caching off, and rebuilds would keep
+        // renaming the wasm under the page.
+        hash_names: opts.hash && !opts.watch,
+        single_file: opts.single_file,
+        out_dir: opts.out_dir.clone(),
+        base_path: page::base_path()?,
+    };
+    if opts.pwa && opts.watch {
+        println!("Note: --watch leaves out the service worker so reloads show the latest build.");
+    }
+    if opts.hash && opts.watch {
+        println!("Note: --watch keeps plain file names; --hash applies to one-off builds.");
+    }
+
+    if opts.watch {
+        return watch::run(&project, opts);
+    }
+
+    build(&project, false, opts.clean)?;
+    println!("\nWeb build ready at: {}/", project.out_dir.display());
+
+    if opts.serve {
+        serve::serve(&project.out_dir, &project.base_path, &opts.host, opts.port, None)?;
+    }
+    Ok(())
+}
+
+/// What a web build needs to know about the project.
+#[derive(Clone)]
+struct Project {
+    crate_name: String,
+    target_dir: PathBuf,
+    /// `wasm-opt` level, if it should run.
+    wasm_opt: Option<String>,
+    /// Whether to precompress the output.
+    compress: bool,
+    /// Whether to write the web app manifest.
+    pwa: bool,
+    /// Whether to write the service worker.
+    service_worker: bool,
+    /// Whether to give the wasm and bundle content-hashed names.
+    hash_names: bool,
+    /// Whether to write `<crate>.html` with everything embedded.
+    single_file: bool,
+    out_dir: PathBuf,
+    /// Where the build is hosted, e.g. `/my-game/`.
+    base_path: String,
+}
+
+impl Project {
+    /// The name `name` is written under: hashed with `--hash`.
+    fn file_name(&self, name: &str, data: &[u8]) -> String {
+        if self.hash_names {
+            fingerprint::hashed_name(name, data)
+        } else {
+            name.to_string()
+        }
+    }
+}
+
+/// Build the project and assemble the output directory, emptying it first
+/// with `clean` (only once cargo has succeeded, so a failed build leaves
+/// the last good one in place). With `capture`, cargo's errors are also
+/// returned in the `Err` (for the watch-mode overlay).
+fn build(project: &Project, capture: bool, clean: bool) -> Result<(), String> {
+    // ── 1. cargo build ──────────────────────────────────────────────────
+    cargo_build(capture)?;
+    if clean {
+        output::clean(&project.out_dir)?;
+    }
+    assemble(project, true)
+}
+
+/// Sync the output directory with the project and the last cargo build.
+/// Without `wasm`, the wasm already there is kept instead of being
+/// optimised again (for asset changes in watch mode).
+fn assemble(project: &Project, wasm: bool) -> Result<(), String> {
+    // ── 2. Open the output directory ────────────────────────────────────
+    let mut out = Output::open(&project.out_dir)?;
+
+    // ── 3. Copy assets/ → <out>/assets/ ─────────────────────────────────
+    copy_assets(&mut out)?;
+
+    // ── 4. Optimise .wasm → <out>/app.wasm ──────────────────────────────
+    let mut names = BTreeMap::new();
+    if wasm {
+        let report = optimize::report_path(&project.out_dir);
+        let data = optimize::optimize(&wasm_path(project)?, project.wasm_opt.as_deref(), &report)?;
+        let name = project.file_name("app.wasm", &data);
+        out.write(&name, &data)?;
+        names.insert("app.wasm".to_string(), name);
+    } else {
+        // Watch mode, the only caller without `wasm`, never hashes names.
+        out.keep("app.wasm");
+    }
+
+    // ── 5. Write the ply_bundle.js matching ply-engine ──────────────────
+    let bundle = bundle::select()?;
+    let name = project.file_name("ply_bundle.js", &bundle.data);
+    out.write(&name, &bundle.data)?;
+    println!("  Wrote ply_bundle.js ({})", bundle.origin);
+    names.insert("ply_bundle.js".to_string(), name);
+
+    // ── 6. Copy the JS plugins → <out>/plugins/ ─────────────────────────
+    let mut plugin_urls = Vec::new();
+    for (url, data) in plugins::load()? {
+        let name = project.file_name(&url, &data);
+        out.write(&name, &data)?;
+        if project.hash_names {
+            names.insert(url.clone(), name);
+        }
+        plugin_urls.push(url);
+    }
+    if !plugin_urls.is_empty() {
+        println!("  Wrote {}", plugin_urls.join(", "));
+    }
+
+    // ── 7. Generate index.html if it doesn't exist ──────────────────────
+    if !Path::new("index.html").exists() {
+        fs::write("index.html", templates::INDEX_HTML)
+            .map_err(|e| format!("Failed to write index.html: {e}"))?;
+        println!("  Generated index.html");
+    }
+
+    // ── 8. Copy index.html → <out>/index.html ───────────────────────────
+    let html = copy_index(project, &mut out, &plugin_urls, &names)?;
+    if project.hash_names {
+        fingerprint::write_manifest(&mut out, &names)?;
+    }
+
+    // ── 9. Single-file export → <out>/<crate>.html ──────────────────────
+    if project.single_file {
+        single_file::write(&mut out, &html, &project.crate_name)?;
+    }
+
+    // ── 10. PWA manifest and service worker ─────────────────────────────
+    if project.pwa {
+        pwa::write_manifest(&mut out, &title(&project.crate_name))?;
+    }
+    if project.service_worker {
+        pwa::write_worker(&mut out, &project.crate_name)?;
+    }
+
+    // ── 11. Precompress ─────────────────────────────────────────────────
+    if project.compress {
+        compress::compress_dir(&mut out)?;
+    }
+
+    // ── 12. Remove files the build no longer produces ───────────────────
+    let removed = out.prune()?;
+    if removed > 0 {
+        println!("  Removed {removed} stale file{}", if removed == 1 { "" } else { "s" });
+    }
+    Ok(())
+}
+
+/// The wasm cargo built. Tries the crate name as-is first (Cargo preserves
+/// hyphens for bin targets), then the underscore variant (lib/cdylib
+/// targets).
+fn wasm_path(project: &Project) -> Result<PathBuf, String> {
+    let crate_name = &project.crate_name;
+    let wasm_dir = project.target_dir.join("wasm32-unknown-unknown/release");
+    let wasm_src = wasm_dir.join(format!("{crate_name}.wasm"));
+    if wasm_src.exists() {
+        return Ok(wasm_src);
+    }
+    let alt = wasm_dir.join(format!("{}.wasm", crate_name.replace('-', "_")));
+    if alt.exists() {
+        return Ok(alt);
+    }
+    Err(format!(
+        "Expected wasm at {} (or with underscores) but neither exists.",
+        wasm_src.display()
+    ))
+}
+
+/// Run `cargo build` for wasm. With `capture`, stderr is passed through
+/// and also kept, so the errors can be returned.
+fn cargo_build(capture: bool) -> Result<(), String> {
+    println!("Building for wasm32-unknown-unknown (release)...");
+    let mut cargo_args = vec!["build", "--release", "--target", "wasm32-unknown-unknown"];
+    if net::is_offline() {
+        cargo_args.push("--offline");
+    }
+    let mut cmd = Command::new("cargo");
+    cmd.args(&cargo_args);
+    if !capture {
+        let status = cmd.status().map_err(|e| format!("Failed to run cargo: {e}"))?;
+        if !status.success() {
+            return Err("cargo build failed.".to_string());
+        }
+        return Ok(());
+    }
+
+    let mut child = cmd
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run cargo: {e}"))?;
+    let mut output = String::new();
+    if let Some(stderr) = child.stderr.take() {
+        for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+            eprintln!("{line}");
+            output.push_str(&line);
+            output.push('\n');
+        }
+    }
+    let status = child.wait().map_err(|e| format!("Failed to run cargo: {e}"))?;
+    if status.success() {
+        return Ok(());
+    }
+    // Skip cargo's progress lines; the diagnostics start at the first error.
+    let lines: Vec<&str> = output.lines().collect();
+    let first = lines.iter().position(|l| l.starts_with("error")).unwrap_or(0);
+    Err(format!("cargo build failed.\n\n{}", lines[first..].join("\n")))
+}
+
+/// Copy `assets/` → `<out>/assets/`, if there is one.
+fn copy_assets(out: &mut Output) -> Result<(), String> {
+    let assets_src = Path::new("assets");
+    if assets_src.exists() {
+        out.copy_dir(assets_src, "assets")?;
+        match out.copy_counts() {
+            (0, _) => println!("  assets/ up to date"),
+            (copied, 0) => println!("  Copied assets/ ({copied} files)"),
+            (copied, unchanged) => {
+                println!("  Copied assets/ ({copied} changed, {unchanged} unchanged)")
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Copy `index.html` → `<out>/index.html`, filling in its variables,
+/// loading the plugins, pointing it at the written file `names` and adding
+/// the PWA tags if needed. Returns the page as written.
+fn copy_index(
+    project: &Project,
+    out: &mut Output,
+    plugin_urls: &[String],
+    names: &BTreeMap<String, String>,
+) -> Result<String, String> {
+    let mut html =
+        fs::read_to_string("index.html").map_err(|e| format!("Failed to read index.html: {e}"))?;
+    let variables =
+        page::Variables::collect(out, &title(&project.crate_name), &project.base_path)?;
+    if !variables.wants_head(&html) {
+        println!("  Tip: put {{{{HEAD}}}} in index.html's <head> for the base path, favicon and meta tags.");
+    }
+    html = variables.render(&html);
+    html = plugins::inject(&html, plugin_urls);
+    if project.hash_names {
+        html = fingerprint::rewrite_all(&html, names);
+    }
+    if project.pwa {
+        html = pwa::inject(&html, project.service_worker);
+    }
+    out.write("index.html", html.as_bytes())?;
+    println!("  Copied index.html");
+    Ok(html)
+}
+
+/// The app's display name: the crate name, title-cased.
+fn title(crate_name: &str) -> String {
+    crate_name
+        .split('-')
+        .map(|w| {
+            let mut c = w.chars();
+            match c.next() {
+                Some(ch) => ch.to_uppercase().to_string() + c.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Recursively copy a directory. Used by `ios` for the app bundle's assets
+/// and by `apk`'s non-Unix fallback; web builds go through [`Output`].
+pub(crate) fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<(), String> {
+    fs::create_dir_all(dst)
+        .map_err(|e| format!("Failed to create {}: {e}", dst.display()))?;
+
+    let entries = fs::read_dir(src)
+        .map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+        let src_path = entry.path();
+        let dst_path = dst.join(entry.file_name());
+
+        if src_path.is_dir() {
+            copy_dir_recursive(&src_path, &dst_path)?;
+        } else {
+            fs::copy(&src_path, &dst_path)
+                .map_err(|e| format!("Failed to copy {}: {e}", src_path.display()))?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,322 @@
+//! Post-build size optimisation for `app.wasm`.
+//!
+//! The wasm cargo produces is run through `wasm-opt` (when a level is
+//! configured and binaryen is installed), then stripped of custom sections:
+//! debug info, symbol names and producer notes that browsers never read.
+//! Each stage's size goes into `size-report.txt` next to the output
+//! directory (`build/size-report.txt` by default).
+//!
+//! ```toml
+//! [package.metadata.plyx.web]
+//! wasm-opt = "z"   # 0-4, "s", "z", or "off"
+//! ```
+//!
+//! `plyx web --size` applies the release profile settings that matter most
+//! for wasm size and defaults `wasm-opt` to `"z"`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config;
+use crate::net::human_size;
+
+/// File name of the size report.
+const REPORT_NAME: &str = "size-report.txt";
+
+/// `[profile.release]` settings applied by `--size`.
+const SIZE_PROFILE: &[(&str, SizeValue)] = &[
+    ("opt-level", SizeValue::Str("z")),
+    ("lto", SizeValue::Bool(true)),
+    ("codegen-units", SizeValue::Int(1)),
+    ("panic", SizeValue::Str("abort")),
+];
+
+#[derive(Clone, Copy)]
+enum SizeValue {
+    Str(&'static str),
+    Bool(bool),
+    Int(i64),
+}
+
+impl SizeValue {
+    fn to_value(self) -> toml_edit::Value {
+        match self {
+            SizeValue::Str(s) => s.into(),
+            SizeValue::Bool(b) => b.into(),
+            SizeValue::Int(i) => i.into(),
+        }
+    }
+}
+
+/// The `wasm-opt` level to use: `web.wasm-opt` from config, else `"z"` with
+/// `--size`, else none.
+pub fn wasm_opt_level(size: bool) -> Result<Option<String>, String> {
+    let level = match config::get_str("web.wasm-opt") {
+        Some(level) => level,
+        None if size => "z".to_string(),
+        None => return Ok(None),
+    };
+    match level.trim().to_lowercase().as_str() {
+        "off" | "false" | "none" => Ok(None),
+        l @ ("0" | "1" | "2" | "3" | "4" | "s" | "z") => Ok(Some(l.to_string())),
+        other => Err(format!(
+            "Invalid web.wasm-opt level '{other}'. Use 0-4, s, z or off."
+        )),
+    }
+}
+
+/// Set the `--size` release profile in `./Cargo.toml`, printing what changed.
+pub fn apply_size_profile() -> Result<(), String> {
+    let text =
+        fs::read_to_string("Cargo.toml").map_err(|e| format!("Failed to read Cargo.toml: {e}"))?;
+    let mut doc: toml_edit::DocumentMut = text
+        .parse()
+        .map_err(|e| format!("Failed to parse Cargo.toml: {e}"))?;
+
+    let profile = doc
+        .entry("profile")
+        .or_insert_with(|| {
+            let mut t = toml_edit::Table::new();
+            t.set_implicit(true);
+            toml_edit::Item::Table(t)
+        })
+        .as_table_mut()
+        .ok_or("[profile] in Cargo.toml is not a table")?
+        .entry("release")
+        .or_insert_with(toml_edit::table)
+        .as_table_mut()
+        .ok_or("[profile.release] in Cargo.toml is not a table")?;
+
+    let mut changed = Vec::new();
+    for (key, value) in SIZE_PROFILE {
+        let value = value.to_value();
+        let current = profile.get(key).and_then(|i| i.as_value()).map(|v| v.to_string());
+        if current.as_deref().map(str::trim) != Some(value.to_string().trim()) {
+            changed.push(format!("{key} = {}", value.to_string().trim()));
+            profile[key] = toml_edit::value(value);
+        }
+    }
+    if changed.is_empty() {
+        return Ok(());
+    }
+    fs::write("Cargo.toml", doc.to_string())
+        .map_err(|e| format!("Failed to write Cargo.toml: {e}"))?;
+    println!("Set [profile.release] {} for a smaller build.", changed.join(", "));
+    if changed.iter().any(|c| c.starts_with("panic")) {
+        println!("  Note: panic = \"abort\" applies to native release builds too.");
+    }
+    Ok(())
+}
+
+/// Whether `[profile.release]` already has every `--size` setting.
+fn has_size_profile() -> bool {
+    let Ok(text) = fs::read_to_string("Cargo.toml") else { return true };
+    let Ok(doc) = text.parse::<toml_edit::DocumentMut>() else { return true };
+    let profile = doc.get("profile").and_then(|p| p.get("release"));
+    SIZE_PROFILE.iter().all(|(key, value)| {
+        profile
+            .and_then(|p| p.get(key))
+            .and_then(|i| i.as_value())
+            .is_some_and(|v| v.to_string().trim() == value.to_value().to_string().trim())
+    })
+}
+
+/// Where the size report for a build into `out_dir` goes: beside it, so it
+/// is neither deployed nor pruned.
+pub fn report_path(out_dir: &Path) -> PathBuf {
+    match out_dir.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent.join(REPORT_NAME),
+        _ => PathBuf::from(REPORT_NAME),
+    }
+}
+
+/// Optimise the wasm at `src`, returning the result, and write the size
+/// report to `report`.
+pub fn optimize(src: &Path, wasm_opt: Option<&str>, report: &Path) -> Result<Vec<u8>, String> {
+    let raw = fs::read(src).map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
+    let mut stages = vec![("cargo build".to_string(), raw.len() as u64)];
+    let mut wasm = raw;
+
+    if let Some(level) = wasm_opt {
+        let optimized = src.with_extension("opt.wasm");
+        match run_wasm_opt(src, &optimized, level) {
+            Ok(()) => {
+                wasm = fs::read(&optimized)
+                    .map_err(|e| format!("Failed to read wasm-opt output: {e}"))?;
+                stages.push((format!("wasm-opt -O{level}"), wasm.len() as u64));
+            }
+            Err(e) => eprintln!("Warning: {e}"),
+        }
+    }
+
+    let stripped = strip_custom_sections(&wasm)?;
+    stages.push(("strip custom sections".to_string(), stripped.wasm.len() as u64));
+
+    let (first, last) = (stages[0].1, stages[stages.len() - 1].1);
+    println!(
+        "  Optimised app.wasm: {} → {} (-{}%)",
+        human_size(first),
+        human_size(last),
+        (first - last.min(first)) * 100 / first.max(1)
+    );
+    if !has_size_profile() {
+        println!("  Tip: `plyx web --size` sets the release profile for a smaller app.wasm.");
+    }
+
+    if let Some(dir) = report.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    }
+    fs::write(report, render_report(&stages, &stripped))
+        .map_err(|e| format!("Failed to write {}: {e}", report.display()))?;
+    Ok(stripped.wasm)
+}
+
+fn run_wasm_opt(src: &Path, dest: &Path, level: &str) -> Result<(), String> {
+    let installed = Command::new("wasm-opt")
+        .arg("--version")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !installed {
+        return Err(format!(
+            "wasm-opt -O{level} is configured but wasm-opt isn't installed; skipping it. \
+             Install binaryen (e.g. `cargo install wasm-opt`)."
+        ));
+    }
+    // wasm-opt reads the enabled features from the target_features
+    // section, which is why it runs before stripping.
+    let status = Command::new("wasm-opt")
+        .arg(format!("-O{level}"))
+        .args(["--strip-debug", "--strip-producers"])
+        .arg(src)
+        .arg("-o")
+        .arg(dest)
+        .status()
+        .map_err(|e| format!("Failed to run wasm-opt: {e}"))?;
+    if !status.success() {
+        return Err("wasm-opt failed; using the unoptimised wasm.".to_string());
+    }
+    Ok(())
+}
+
+/// A wasm module with its custom sections removed.
+pub struct Stripped {
+    pub wasm: Vec<u8>,
+    /// Size of each remaining section, by name.
+    pub kept: Vec<(String, u64)>,
+    /// Size of each removed custom section, by name.
+    pub removed: Vec<(String, u64)>,
+}
+
+/// Drop every custom section (debug info, `name`, `producers`, …).
+pub fn strip_custom_sections(wasm: &[u8]) -> Result<Stripped, String> {
+    if wasm.len() < 8 || &wasm[..4] != b"\0asm" {
+        return Err("app.wasm is not a WebAssembly module".to_string());
+    }
+    let invalid = || "app.wasm is truncated or malformed".to_string();
+
+    let mut out = wasm[..8].to_vec();
+    let (mut kept, mut removed) = (Vec::new(), Vec::new());
+    let mut pos = 8;
+    while pos < wasm.len() {
+        let id = wasm[pos];
+        let (size, header) = read_leb(&wasm[pos + 1..]).ok_or_else(invalid)?;
+        let start = pos + 1 + header;
+        let end = start.checked_add(size as usize).filter(|e| *e <= wasm.len()).ok_or_else(invalid)?;
+        let total = (end - pos) as u64;
+        if id == 0 {
+            let (len, n) = read_leb(&wasm[start..end]).ok_or_else(invalid)?;
+            let name = wasm
+                .get(start + n..start + n + len as usize)
+                .map(|b| String::from_utf8_lossy(b).into_owned())
+                .ok_or_else(invalid)?;
+            removed.push((name, total));
+        } else {
+            kept.push((section_name(id).to_string(), total));
+            out.extend_from_slice(&wasm[pos..end]);
+        }
+        pos = end;
+    }
+    Ok(Stripped { wasm: out, kept, removed })
+}
+
+/// Unsigned LEB128; returns the value and the number of bytes read.
+fn read_leb(bytes: &[u8]) -> Option<(u32, usize)> {
+    let mut value: u32 = 0;
+    for (i, byte) in bytes.iter().take(5).enumerate() {
+        value |= u32::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some((value, i + 1));
+        }
+    }
+    None
+}
+
+fn section_name(id: u8) -> &'static str {
+    match id {
+        1 => "type",
+        2 => "import",
+        3 => "function",
+        4 => "table",
+        5 => "memory",
+        6 => "global",
+        7 => "export",
+        8 => "start",
+        9 => "element",
+        10 => "code",
+        11 => "data",
+        12 => "data count",
+        13 => "tag",
+        _ => "unknown",
+    }
+}
+
+fn render_report(stages: &[(String, u64)], stripped: &Stripped) -> String {
+    let mut out = String::from("app.wasm size report (written by plyx web)\n\nStage\n");
+    for (stage, size) in stages {
+        out.push_str(&format!("  {stage:<24}{:>10}\n", human_size(*size)));
+    }
+    let mut table = |title: &str, rows: &[(String, u64)]| {
+        if rows.is_empty() {
+            return;
+        }
+        let mut rows = rows.to_vec();
+        rows.sort_by_key(|row| std::cmp::Reverse(row.1));
+        out.push_str(&format!("\n{title}\n"));
+        for (name, size) in rows {
+            out.push_str(&format!("  {name:<24}{:>10}\n", human_size(size)));
+        }
+    };
+    table("Sections", &stripped.kept);
+    table("Removed custom sections", &stripped.removed);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_custom_sections() {
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.extend_from_slice(&[1, 4, 1, 0x60, 0, 0]); // type section
+        wasm.extend_from_slice(&[0, 8, 4]); // custom section "name"
+        wasm.extend_from_slice(b"name\x01\x02\x03");
+        wasm.extend_from_slice(&[3, 2, 1, 0]); // function section
+
+        let stripped = strip_custom_sections(&wasm).unwrap();
+        assert_eq!(stripped.wasm, b"\0asm\x01\0\0\0\x01\x04\x01\x60\0\0\x03\x02\x01\0");
+        assert_eq!(stripped.removed, [("name".to_string(), 10)]);
+        assert_eq!(stripped.kept.len(), 2);
+
+        assert!(strip_custom_sections(&wasm[..wasm.len() - 1]).is_err());
+        assert!(strip_custom_sections(b"not wasm").is_err());
+        assert_eq!(read_leb(&[0xe5, 0x8e, 0x26]), Some((624_485, 3)));
+    }
+
+    #[test]
+    fn test_report_path() {
+        assert_eq!(report_path(Path::new("build/web")), Path::new("build/size-report.txt"));
+        assert_eq!(report_path(Path::new("build")), Path::new("size-report.txt"));
+    }
+}
@@ -0,0 +1,288 @@
+//! The web output directory, kept in sync with the project.
+//!
+//! Every file a build produces goes through [`Output`], which skips files
+//! whose contents haven't changed (so their mtimes, and anything caching
+//! on them, survive) and remembers what it was given. [`Output::prune`]
+//! then removes everything else: assets deleted from `assets/`, files from
+//! flags that are no longer passed, old hashed names.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// Marks a directory as plyx's, so pruning never empties a directory it
+/// didn't create.
+const MARKER: &str = ".plyx-web";
+
+/// Where the web build goes, unless `--out-dir` says otherwise.
+pub const DEFAULT_DIR: &str = "build/web";
+
+/// The files of one build of the output directory.
+pub struct Output {
+    dir: PathBuf,
+    kept: BTreeSet<PathBuf>,
+    copied: usize,
+    unchanged: usize,
+}
+
+impl Output {
+    /// Start a build into `dir`, creating it if needed. Refuses a
+    /// non-empty directory plyx didn't create, and any directory holding
+    /// the project itself.
+    pub fn open(dir: &Path) -> Result<Output, String> {
+        check_location(dir)?;
+        if dir.exists() && !dir.join(MARKER).exists() && dir != Path::new(DEFAULT_DIR) {
+            let empty = fs::read_dir(dir)
+                .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+                .next()
+                .is_none();
+            if !empty {
+                return Err(format!(
+                    "{} isn't empty and wasn't created by plyx web, so syncing it could delete \
+                     your files. Empty it yourself, or choose another --out-dir.",
+                    dir.display()
+                ));
+            }
+        }
+        fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+        let marker = dir.join(MARKER);
+        if !marker.exists() {
+            fs::write(&marker, "Created by plyx web; removed files are synced away.\n")
+                .map_err(|e| format!("Failed to write {}: {e}", marker.display()))?;
+        }
+        Ok(Output {
+            dir: dir.to_path_buf(),
+            kept: BTreeSet::new(),
+            copied: 0,
+            unchanged: 0,
+        })
+    }
+
+    /// The full path of `rel`.
+    pub fn path(&self, rel: impl AsRef<Path>) -> PathBuf {
+        self.dir.join(rel)
+    }
+
+    /// The files produced so far, relative to the directory.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.kept.iter().map(PathBuf::as_path)
+    }
+
+    /// Keep a file that is already in place.
+    pub fn keep(&mut self, rel: impl AsRef<Path>) {
+        self.kept.insert(rel.as_ref().to_path_buf());
+    }
+
+    /// Write `data` to `rel`, unless it already holds exactly that. Returns
+    /// whether the file changed.
+    pub fn write(&mut self, rel: impl AsRef<Path>, data: &[u8]) -> Result<bool, String> {
+        let rel = rel.as_ref();
+        let dest = self.path(rel);
+        self.keep(rel);
+        let same = fs::metadata(&dest).is_ok_and(|m| m.len() == data.len() as u64)
+            && fs::read(&dest).is_ok_and(|old| old == data);
+        if same {
+            return Ok(false);
+        }
+        create_parent(&dest)?;
+        fs::write(&dest, data).map_err(|e| format!("Failed to write {}: {e}", dest.display()))?;
+        Ok(true)
+    }
+
+    /// Copy `src` to `rel`, skipping it when the copy is up to date: same
+    /// size and newer than `src`, or same contents.
+    pub fn copy(&mut self, src: &Path, rel: impl AsRef<Path>) -> Result<bool, String> {
+        let rel = rel.as_ref();
+        let dest = self.path(rel);
+        self.keep(rel);
+        if is_up_to_date(src, &dest) {
+            self.unchanged += 1;
+            return Ok(false);
+        }
+        create_parent(&dest)?;
+        fs::copy(src, &dest).map_err(|e| format!("Failed to copy {}: {e}", src.display()))?;
+        self.copied += 1;
+        Ok(true)
+    }
+
+    /// Copy the tree at `src` to `rel`.
+    pub fn copy_dir(&mut self, src: &Path, rel: impl AsRef<Path>) -> Result<(), String> {
+        let entries =
+            fs::read_dir(src).map_err(|e| format!("Failed to read {}: {e}", src.display()))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {e}"))?;
+            let (src_path, dst_rel) = (entry.path(), rel.as_ref().join(entry.file_name()));
+            if src_path.is_dir() {
+                self.copy_dir(&src_path, dst_rel)?;
+            } else {
+                self.copy(&src_path, dst_rel)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Files copied and skipped by [`Output::copy`] so far.
+    pub fn copy_counts(&self) -> (usize, usize) {
+        (self.copied, self.unchanged)
+    }
+
+    /// Remove every file this build didn't produce, and any directories
+    /// left empty. Returns how many files went.
+    pub fn prune(&self) -> Result<usize, String> {
+        let mut removed = 0;
+        self.prune_dir(&self.dir, &mut removed)?;
+        Ok(removed)
+    }
+
+    fn prune_dir(&self, dir: &Path, removed: &mut usize) -> Result<(), String> {
+        let entries = fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {e}", dir.display()))?;
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Failed to read entry: {e}"))?.path();
+            let rel = path.strip_prefix(&self.dir).unwrap_or(&path);
+            if path.is_dir() {
+                self.prune_dir(&path, removed)?;
+                if fs::read_dir(&path).is_ok_and(|mut d| d.next().is_none()) {
+                    fs::remove_dir(&path)
+                        .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+                }
+            } else if rel != Path::new(MARKER) && !self.kept.contains(rel) {
+                fs::remove_file(&path)
+                    .map_err(|e| format!("Failed to remove {}: {e}", path.display()))?;
+                *removed += 1;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Delete the output directory, for `--clean`. Only a directory plyx
+/// created (or an empty one) is removed; anything else is refused.
+pub fn clean(dir: &Path) -> Result<(), String> {
+    check_location(dir)?;
+    if !dir.exists() {
+        return Ok(());
+    }
+    let empty = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read {}: {e}", dir.display()))?
+        .next()
+        .is_none();
+    if !empty && !dir.join(MARKER).exists() {
+        return Err(format!(
+            "{} wasn't created by plyx web, so --clean won't delete it. Remove it yourself, \
+             or choose another --out-dir.",
+            dir.display()
+        ));
+    }
+    fs::remove_dir_all(dir).map_err(|e| format!("Failed to remove {}: {e}", dir.display()))?;
+    println!("Removed {}", dir.display());
+    Ok(())
+}
+
+/// Refuse output directories that would swallow the project: the project
+/// root or one of its parents, or a directory plyx reads sources from.
+fn check_location(dir: &Path) -> Result<(), String> {
+    let root = std::env::current_dir().map_err(|e| format!("Failed to read current dir: {e}"))?;
+    let out = absolute(&root, dir);
+    if root.starts_with(&out) {
+        return Err(format!(
+            "--out-dir {} contains the project itself; choose a subdirectory such as build/web.",
+            dir.display()
+        ));
+    }
+    for source in ["src", "assets", "shaders", "web"] {
+        if out.starts_with(root.join(source)) {
+            return Err(format!(
+                "--out-dir {} is inside {source}/, which the build reads from.",
+                dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// `dir` as an absolute path with `.` and `..` resolved, without touching
+/// the file system (it may not exist yet).
+fn absolute(root: &Path, dir: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in root.join(dir).components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                out.pop();
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn is_up_to_date(src: &Path, dest: &Path) -> bool {
+    let (Ok(src_meta), Ok(dest_meta)) = (fs::metadata(src), fs::metadata(dest)) else {
+        return false;
+    };
+    if src_meta.len() != dest_meta.len() {
+        return false;
+    }
+    let newer = match (src_meta.modified(), dest_meta.modified()) {
+        (Ok(src_time), Ok(dest_time)) => dest_time >= src_time,
+        _ => false,
+    };
+    newer || hash_file(src).is_some_and(|h| hash_file(dest) == Some(h))
+}
+
+fn hash_file(path: &Path) -> Option<Vec<u8>> {
+    let mut file = fs::File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher).ok()?;
+    Some(hasher.finalize().to_vec())
+}
+
+fn create_parent(path: &Path) -> Result<(), String> {
+    match path.parent() {
+        Some(parent) => fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {e}", parent.display())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sync() {
+        let base = std::env::temp_dir().join(format!("plyx-output-{}", std::process::id()));
+        let (src, dir) = (base.join("assets"), base.join("out"));
+        fs::create_dir_all(src.join("fonts")).unwrap();
+        fs::write(src.join("fonts/a.ttf"), "font").unwrap();
+        fs::write(src.join("logo.png"), "logo").unwrap();
+
+        let mut out = Output::open(&dir).unwrap();
+        out.copy_dir(&src, "assets").unwrap();
+        assert!(out.write("index.html", b"<html>").unwrap());
+        assert_eq!(out.copy_counts(), (2, 0));
+        out.prune().unwrap();
+
+        fs::remove_file(src.join("fonts/a.ttf")).unwrap();
+        let mut out = Output::open(&dir).unwrap();
+        out.copy_dir(&src, "assets").unwrap();
+        assert!(!out.write("index.html", b"<html>").unwrap());
+        assert_eq!(out.copy_counts(), (0, 1));
+        assert_eq!(out.prune().unwrap(), 1);
+        assert!(!dir.join("assets/fonts").exists());
+        assert!(dir.join("assets/logo.png").is_file());
+
+        fs::write(base.join("mine.txt"), "").unwrap();
+        assert!(Output::open(&base).is_err());
+        assert!(clean(&base).is_err());
+        clean(&dir).unwrap();
+        assert!(!dir.exists());
+        fs::remove_dir_all(&base).unwrap();
+
+        let root = Path::new("/p");
+        assert_eq!(absolute(root, Path::new("build/../dist/./web")), Path::new("/p/dist/web"));
+        assert_eq!(absolute(root, Path::new("..")), Path::new("/"));
+    }
+}
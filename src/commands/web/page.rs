@@ -0,0 +1,239 @@
+//! Build-time variables for `index.html`.
+//!
+//! The generated page keeps its `{{VARIABLES}}`, and so can a customised
+//! one: they are filled in every time the page is copied into the build.
+//!
+//! | Variable           | Value                                              |
+//! |--------------------|----------------------------------------------------|
+//! | `{{TITLE}}`        | `web.name`, or the crate name title-cased          |
+//! | `{{NAME}}`         | The crate name                                     |
+//! | `{{VERSION}}`      | The package version                                |
+//! | `{{DESCRIPTION}}`  | `web.description`, or the package description      |
+//! | `{{BASE_PATH}}`    | `web.base-path`, e.g. `/my-game/` (default `/`)     |
+//! | `{{HEAD}}`         | `<base>`, favicon and social meta tags             |
+//! | `{{TAP_TO_START}}` | `true` when the `audio` feature is on              |
+//!
+//! ```toml
+//! [package.metadata.plyx.web]
+//! base-path = "/my-game/"
+//! favicon = "assets/favicon.png"   # default: assets/favicon.{ico,png,svg}
+//! image = "assets/preview.png"     # og:image / twitter:image
+//! url = "https://example.com/my-game/"
+//! ```
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::output::Output;
+use super::serve::content_type;
+use crate::config;
+
+/// Favicons picked up without configuration, in order.
+const DEFAULT_FAVICONS: &[&str] = &["assets/favicon.ico", "assets/favicon.png", "assets/favicon.svg"];
+
+/// The values of the page variables.
+pub struct Variables {
+    values: BTreeMap<&'static str, String>,
+    /// Raw HTML for `{{HEAD}}`, one tag per entry.
+    head: Vec<String>,
+    /// Whether the head has anything the project set: a base path,
+    /// favicon, description, preview image or URL.
+    configured: bool,
+}
+
+impl Variables {
+    /// Collect the variables for this build, copying the favicon and
+    /// preview image into `out` if they live outside `assets/`.
+    pub fn collect(out: &mut Output, title: &str, base_path: &str) -> Result<Variables, String> {
+        let package = package_table();
+        let package_str = |key: &str| package.as_ref().and_then(|p| p.get(key)?.as_str().map(str::to_string));
+        let title = config::get_str("web.name").unwrap_or_else(|| title.to_string());
+        let description = config::get_str("web.description")
+            .or_else(|| package_str("description"))
+            .unwrap_or_default();
+        let cargo = fs::read_to_string("Cargo.toml").unwrap_or_default();
+        let audio = crate::commands::add::detect_enabled_features(&cargo).iter().any(|f| f == "audio");
+
+        let mut head = Vec::new();
+        if base_path != "/" {
+            head.push(format!("<base href=\"{}\">", escape(base_path)));
+        }
+        if let Some(favicon) = config::get_str("web.favicon")
+            .or_else(|| DEFAULT_FAVICONS.iter().find(|f| Path::new(f).is_file()).map(|f| f.to_string()))
+        {
+            let href = publish(out, &favicon)?;
+            head.push(format!(
+                "<link rel=\"icon\" href=\"{}\" type=\"{}\">",
+                escape(&href),
+                content_type(Path::new(&favicon))
+            ));
+        }
+        if !description.is_empty() {
+            head.push(meta("name", "description", &description));
+        }
+        let configured = !head.is_empty()
+            || config::get_str("web.image").is_some()
+            || config::get_str("web.url").is_some();
+        head.extend(social_tags(out, &title, &description)?);
+
+        let values = BTreeMap::from([
+            ("TITLE", escape(&title)),
+            ("NAME", escape(&package_str("name").unwrap_or_default())),
+            ("VERSION", escape(&package_str("version").unwrap_or_default())),
+            ("DESCRIPTION", escape(&description)),
+            ("BASE_PATH", escape(base_path)),
+            ("TAP_TO_START", audio.to_string()),
+        ]);
+        Ok(Variables { values, head, configured })
+    }
+
+    /// Fill in the variables in `html`, warning about unknown ones.
+    pub fn render(&self, html: &str) -> String {
+        let mut out = String::with_capacity(html.len());
+        for line in html.split_inclusive('\n') {
+            if line.trim() == "{{HEAD}}" {
+                // One tag per line at the placeholder's indentation, or no
+                // line at all when there are no tags.
+                let indent = &line[..line.len() - line.trim_start().len()];
+                for tag in &self.head {
+                    out.push_str(&format!("{indent}{tag}\n"));
+                }
+                continue;
+            }
+            out.push_str(line);
+        }
+        let mut html = out.replace("{{HEAD}}", &self.head.join(""));
+        for (name, value) in &self.values {
+            html = html.replace(&format!("{{{{{name}}}}}"), value);
+        }
+        for unknown in placeholders(&html) {
+            eprintln!("Warning: index.html uses unknown variable {{{{{unknown}}}}}.");
+        }
+        html
+    }
+
+    /// Whether `html` has a place for the `{{HEAD}}` tags, or only the
+    /// default social tags would go there.
+    pub fn wants_head(&self, html: &str) -> bool {
+        !self.configured || html.contains("{{HEAD}}")
+    }
+}
+
+/// `web.base-path` as `/path/`, or `/`.
+pub fn base_path() -> Result<String, String> {
+    let Some(path) = config::get_str("web.base-path") else { return Ok("/".to_string()) };
+    let path = path.trim().trim_matches('/');
+    if path.contains(['"', '\'', '<', '>', '?', '#', ' ', '\\']) || path.contains("://") {
+        return Err(format!(
+            "Invalid web.base-path '{path}'. Use a URL path such as \"/my-game/\"."
+        ));
+    }
+    Ok(if path.is_empty() { "/".to_string() } else { format!("/{path}/") })
+}
+
+fn social_tags(out: &mut Output, title: &str, description: &str) -> Result<Vec<String>, String> {
+    let url = config::get_str("web.url").map(|u| format!("{}/", u.trim_end_matches('/')));
+    let mut tags = vec![
+        meta("property", "og:type", "website"),
+        meta("property", "og:title", title),
+    ];
+    if !description.is_empty() {
+        tags.push(meta("property", "og:description", description));
+    }
+    if let Some(url) = &url {
+        tags.push(meta("property", "og:url", url));
+    }
+    let card = match config::get_str("web.image") {
+        Some(image) => {
+            let href = publish(out, &image)?;
+            // Scrapers need an absolute URL, which only `web.url` gives.
+            let src = match &url {
+                Some(url) => format!("{url}{href}"),
+                None => href,
+            };
+            tags.push(meta("property", "og:image", &src));
+            tags.push(meta("name", "twitter:image", &src));
+            "summary_large_image"
+        }
+        None => "summary",
+    };
+    tags.push(meta("name", "twitter:card", card));
+    Ok(tags)
+}
+
+/// The URL of a project file in the build: files under `assets/` are
+/// copied with it, others are copied to the root of `out`.
+fn publish(out: &mut Output, path: &str) -> Result<String, String> {
+    let src = Path::new(path);
+    if !src.is_file() {
+        return Err(format!("{path} (from [package.metadata.plyx.web]) doesn't exist."));
+    }
+    if let Ok(rel) = src.strip_prefix("assets") {
+        let rel: Vec<_> = rel.iter().map(|p| p.to_string_lossy()).collect();
+        return Ok(format!("assets/{}", rel.join("/")));
+    }
+    let name = src.file_name().ok_or_else(|| format!("Invalid path {path}"))?;
+    out.copy(src, name)?;
+    Ok(name.to_string_lossy().into_owned())
+}
+
+fn meta(attr: &str, key: &str, content: &str) -> String {
+    format!("<meta {attr}=\"{key}\" content=\"{}\">", escape(content))
+}
+
+/// `{{NAME}}` placeholders left in `html`.
+fn placeholders(html: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("{{") {
+        rest = &rest[start + 2..];
+        let Some(end) = rest.find("}}") else { break };
+        let name = &rest[..end];
+        if !name.is_empty() && name.bytes().all(|b| b.is_ascii_uppercase() || b == b'_') {
+            names.push(name);
+        }
+        rest = &rest[end + 2..];
+    }
+    names
+}
+
+fn package_table() -> Option<toml_edit::Table> {
+    let text = fs::read_to_string("Cargo.toml").ok()?;
+    let doc: toml_edit::DocumentMut = text.parse().ok()?;
+    doc.get("package")?.as_table().cloned()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let vars = Variables {
+            values: BTreeMap::from([("TITLE", escape("Tom & Jerry")), ("VERSION", "0.3.1".to_string())]),
+            head: vec!["<base href=\"/game/\">".to_string(), meta("name", "description", "A \"game\"")],
+            configured: true,
+        };
+        let html = vars.render(
+            "<head>\n    <title>{{TITLE}} v{{VERSION}}</title>\n    {{HEAD}}\n</head>\n{{OTHER}}",
+        );
+        assert_eq!(
+            html,
+            "<head>\n    <title>Tom &amp; Jerry v0.3.1</title>\n    <base href=\"/game/\">\n    \
+             <meta name=\"description\" content=\"A &quot;game&quot;\">\n</head>\n{{OTHER}}"
+        );
+        assert!(!vars.wants_head("<head></head>"));
+        let empty = Variables { values: BTreeMap::new(), head: Vec::new(), configured: false };
+        assert_eq!(empty.render("<head>\n  {{HEAD}}\n</head>"), "<head>\n</head>");
+        assert!(empty.wants_head("<head></head>"));
+        assert_eq!(placeholders("{{A_B}} {{x}} {{C}}"), ["A_B", "C"]);
+    }
+}
@@ -0,0 +1,118 @@
+//! Extra scripts loaded after `ply_bundle.js`: analytics, miniquad plugins,
+//! bridges to browser APIs.
+//!
+//! Plugins are the `*.js` files in `web/plugins/` in file-name order, or
+//! the list in config. They are copied to `plugins/` in the build and get
+//! a `<script>` tag each right after the bundle's, so they run before the
+//! app is loaded. `bundle-plugins` joins them, unchanged, into one
+//! `plugins.js` instead.
+//!
+//! ```toml
+//! [package.metadata.plyx.web]
+//! plugins = ["js/analytics.js", "web/plugins/gamepad.js"]
+//! bundle-plugins = true
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config;
+
+/// Where plugins are picked up from without configuration.
+pub const DIR: &str = "web/plugins";
+
+/// The plugin scripts to ship, as (URL in the build, contents), in load
+/// order.
+pub fn load() -> Result<Vec<(String, Vec<u8>)>, String> {
+    let sources = sources()?;
+    if sources.is_empty() {
+        return Ok(Vec::new());
+    }
+    let read = |src: &Path| fs::read(src).map_err(|e| format!("Failed to read {}: {e}", src.display()));
+
+    if config::get_bool("web.bundle-plugins") == Some(true) {
+        let mut joined = String::new();
+        for src in &sources {
+            let text = String::from_utf8(read(src)?)
+                .map_err(|_| format!("{} isn't UTF-8 text", src.display()))?;
+            // The `;` on its own line ends a plugin's last statement even
+            // when it stops on a line comment or lacks a semicolon.
+            joined.push_str(&format!("/* {} */\n{}\n;\n", src.display(), text.trim_end()));
+        }
+        return Ok(vec![("plugins.js".to_string(), joined.into_bytes())]);
+    }
+
+    let mut plugins: Vec<(String, Vec<u8>)> = Vec::new();
+    for src in &sources {
+        let name = src.file_name().map(|n| n.to_string_lossy()).unwrap_or_default();
+        let url = format!("plugins/{name}");
+        if plugins.iter().any(|(u, _)| *u == url) {
+            return Err(format!("Two plugins are named {name}; rename one of them."));
+        }
+        plugins.push((url, read(src)?));
+    }
+    Ok(plugins)
+}
+
+/// `web.plugins` from config, else `web/plugins/*.js` sorted by name.
+fn sources() -> Result<Vec<PathBuf>, String> {
+    if let Some(list) = config::get_list("web.plugins") {
+        let paths: Vec<PathBuf> = list.iter().map(PathBuf::from).collect();
+        if let Some(missing) = paths.iter().find(|p| !p.is_file()) {
+            return Err(format!("Plugin {} (from web.plugins) doesn't exist.", missing.display()));
+        }
+        return Ok(paths);
+    }
+    let Ok(entries) = fs::read_dir(DIR) else { return Ok(Vec::new()) };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && p.extension().is_some_and(|e| e == "js"))
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Add a `<script>` tag for each of `urls` right after the bundle's.
+pub fn inject(html: &str, urls: &[String]) -> String {
+    if urls.is_empty() {
+        return html.to_string();
+    }
+    let tag = ["\"ply_bundle.js\"", "'ply_bundle.js'", "\"./ply_bundle.js\"", "'./ply_bundle.js'"]
+        .iter()
+        .filter_map(|src| html.find(src))
+        .min()
+        .and_then(|at| html[at..].find("</script>").map(|end| at + end + "</script>".len()));
+    let Some(end) = tag else {
+        eprintln!(
+            "Warning: index.html has no <script src=\"ply_bundle.js\"> tag to put the plugins \
+             after; add <script> tags for {} yourself.",
+            urls.join(", ")
+        );
+        return html.to_string();
+    };
+    let line_start = html[..end].rfind('\n').map_or(0, |i| i + 1);
+    let indent: String = html[line_start..].chars().take_while(|c| c.is_whitespace()).collect();
+    let tags: String = urls
+        .iter()
+        .map(|url| format!("\n{indent}<script src=\"{url}\"></script>"))
+        .collect();
+    format!("{}{tags}{}", &html[..end], &html[end..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject() {
+        let html = "<body>\n    <script src=\"ply_bundle.js\"></script>\n    <script>load();</script>";
+        assert_eq!(
+            inject(html, &["plugins/a.js".to_string(), "plugins/b.js".to_string()]),
+            "<body>\n    <script src=\"ply_bundle.js\"></script>\n    \
+             <script src=\"plugins/a.js\"></script>\n    <script src=\"plugins/b.js\"></script>\n    \
+             <script>load();</script>"
+        );
+        assert_eq!(inject("<body></body>", &["plugins/a.js".to_string()]), "<body></body>");
+    }
+}
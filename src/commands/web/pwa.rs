@@ -0,0 +1,252 @@
+//! `plyx web --pwa` — a web app manifest and an offline service worker.
+//!
+//! ```toml
+//! [package.metadata.plyx.web]
+//! name = "Space Trader"      # defaults to the crate name, title-cased
+//! short-name = "Trader"
+//! theme-color = "#1e1e2e"
+//! background-color = "#000000"
+//! icons = ["assets/icon-192.png", "assets/icon-512.png"]
+//! ```
+//!
+//! Without `icons`, every `assets/icon*.png` is used. The service worker
+//! precaches the whole build under a cache name derived from the content
+//! hashes, so each new build gets a fresh cache and the old one is dropped.
+
+use std::fs;
+use std::path::{Component, Path};
+
+use sha2::{Digest, Sha256};
+
+use super::output::Output;
+use super::serve::content_type;
+use crate::config;
+
+pub const MANIFEST: &str = "manifest.webmanifest";
+pub const WORKER: &str = "sw.js";
+
+const DEFAULT_COLOR: &str = "#000000";
+
+const WORKER_JS: &str = r#"const CACHE = "{{CACHE}}";
+const PREFIX = "{{PREFIX}}";
+const FILES = {{FILES}};
+
+self.addEventListener("install", (event) => {
+  event.waitUntil(
+    caches
+      .open(CACHE)
+      .then((cache) => cache.addAll(FILES.map((file) => new Request(file, { cache: "reload" }))))
+      .then(() => self.skipWaiting())
+  );
+});
+
+self.addEventListener("activate", (event) => {
+  event.waitUntil(
+    caches
+      .keys()
+      .then((keys) => keys.filter((key) => key.startsWith(PREFIX) && key !== CACHE))
+      .then((old) => Promise.all(old.map((key) => caches.delete(key))))
+      .then(() => self.clients.claim())
+  );
+});
+
+self.addEventListener("fetch", (event) => {
+  const request = event.request;
+  if (request.method !== "GET" || new URL(request.url).origin !== location.origin) {
+    return;
+  }
+  event.respondWith(
+    caches.open(CACHE).then((cache) =>
+      cache.match(request, { ignoreSearch: true }).then(
+        (hit) =>
+          hit ||
+          fetch(request).catch(() =>
+            request.mode === "navigate" ? cache.match("./") : Response.error()
+          )
+      )
+    )
+  );
+});
+"#;
+
+const REGISTER_JS: &str = r#"    <script>
+        if ("serviceWorker" in navigator) {
+            navigator.serviceWorker.register("sw.js");
+        }
+    </script>
+"#;
+
+/// Add the manifest link and theme colour to a page, and the service
+/// worker registration with `worker`. Tags the page already has are left
+/// alone.
+pub fn inject(html: &str, worker: bool) -> String {
+    let theme = config::get_str("web.theme-color").unwrap_or_else(|| DEFAULT_COLOR.to_string());
+    let mut head = String::new();
+    if !html.contains("rel=\"manifest\"") {
+        head.push_str(&format!("    <link rel=\"manifest\" href=\"{MANIFEST}\">\n"));
+    }
+    if !html.contains("name=\"theme-color\"") {
+        head.push_str(&format!("    <meta name=\"theme-color\" content=\"{theme}\">\n"));
+    }
+    let html = insert_before(html, "</head>", &head);
+    if worker && !html.contains("serviceWorker") {
+        insert_before(&html, "</body>", REGISTER_JS)
+    } else {
+        html
+    }
+}
+
+fn insert_before(html: &str, tag: &str, text: &str) -> String {
+    match html.rfind(tag) {
+        Some(i) => format!("{}{text}{}", &html[..i], &html[i..]),
+        None => format!("{html}{text}"),
+    }
+}
+
+/// Write `manifest.webmanifest` into `out`, copying in any icons that live
+/// outside `assets/`.
+pub fn write_manifest(out: &mut Output, title: &str) -> Result<(), String> {
+    let name = config::get_str("web.name").unwrap_or_else(|| title.to_string());
+    let short_name = config::get_str("web.short-name").unwrap_or_else(|| name.clone());
+    let color = |key: &str| config::get_str(key).unwrap_or_else(|| DEFAULT_COLOR.to_string());
+
+    let mut manifest = serde_json::json!({
+        "name": name,
+        "short_name": short_name,
+        "start_url": ".",
+        "scope": ".",
+        "display": "standalone",
+        "background_color": color("web.background-color"),
+        "theme_color": color("web.theme-color"),
+        "icons": icons(out)?,
+    });
+    if let Some(description) = package_description() {
+        manifest["description"] = description.into();
+    }
+
+    let json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to encode {MANIFEST}: {e}"))?;
+    out.write(MANIFEST, (json + "\n").as_bytes())?;
+    println!("  Wrote {MANIFEST}");
+    Ok(())
+}
+
+fn icons(out: &mut Output) -> Result<Vec<serde_json::Value>, String> {
+    let paths = config::get_list("web.icons").unwrap_or_else(default_icons);
+    if paths.is_empty() {
+        eprintln!(
+            "Warning: no icons for {MANIFEST}; browsers only offer to install apps with one. \
+             Add assets/icon-512.png or set web.icons."
+        );
+    }
+
+    let mut icons = Vec::new();
+    for path in paths {
+        let src = Path::new(&path);
+        let data = fs::read(src).map_err(|e| format!("Failed to read icon {path}: {e}"))?;
+        let href = match src.strip_prefix("assets") {
+            Ok(rel) => format!("assets/{}", url_path(rel)),
+            Err(_) => {
+                let name = src.file_name().ok_or_else(|| format!("Invalid icon path {path}"))?;
+                out.write(name, &data)?;
+                name.to_string_lossy().into_owned()
+            }
+        };
+        let sizes = png_size(&data).map_or("any".to_string(), |(w, h)| format!("{w}x{h}"));
+        icons.push(serde_json::json!({
+            "src": href,
+            "sizes": sizes,
+            "type": content_type(src),
+        }));
+    }
+    Ok(icons)
+}
+
+/// `assets/icon*.png`, sorted.
+fn default_icons() -> Vec<String> {
+    let Ok(entries) = fs::read_dir("assets") else { return Vec::new() };
+    let mut icons: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("icon") && name.ends_with(".png"))
+        .map(|name| format!("assets/{name}"))
+        .collect();
+    icons.sort();
+    icons
+}
+
+/// Width and height from a PNG header.
+fn png_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.len() < 24 || &data[..8] != b"\x89PNG\r\n\x1a\n" || &data[12..16] != b"IHDR" {
+        return None;
+    }
+    let read = |at: usize| u32::from_be_bytes(data[at..at + 4].try_into().unwrap());
+    Some((read(16), read(20)))
+}
+
+fn package_description() -> Option<String> {
+    let text = fs::read_to_string("Cargo.toml").ok()?;
+    let doc: toml_edit::DocumentMut = text.parse().ok()?;
+    doc.get("package")?.get("description")?.as_str().map(str::to_string)
+}
+
+/// Write `sw.js` into `out`, precaching every file of the build so far.
+pub fn write_worker(out: &mut Output, crate_name: &str) -> Result<(), String> {
+    let files: Vec<String> = out.files().map(url_path).collect();
+
+    let mut hasher = Sha256::new();
+    for file in &files {
+        let data = fs::read(out.path(file)).map_err(|e| format!("Failed to read {file}: {e}"))?;
+        hasher.update(file.as_bytes());
+        hasher.update([0]);
+        hasher.update(Sha256::digest(&data));
+    }
+    let hash: String = hasher.finalize().iter().take(6).map(|b| format!("{b:02x}")).collect();
+
+    let mut urls = vec!["./".to_string()];
+    urls.extend(files);
+    let prefix = format!("{crate_name}-");
+    let js = WORKER_JS
+        .replace("{{CACHE}}", &format!("{prefix}{hash}"))
+        .replace("{{PREFIX}}", &prefix)
+        .replace(
+            "{{FILES}}",
+            &serde_json::to_string_pretty(&urls).map_err(|e| format!("Failed to encode {WORKER}: {e}"))?,
+        );
+    out.write(WORKER, js.as_bytes())?;
+    println!("  Wrote {WORKER} ({} files precached)", urls.len());
+    Ok(())
+}
+
+fn url_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inject() {
+        let html = "<html>\n<head>\n    <title>x</title>\n</head>\n<body>\n</body>\n</html>";
+        let injected = inject(html, true);
+        assert!(injected.contains(
+            "</title>\n    <link rel=\"manifest\" href=\"manifest.webmanifest\">\n    \
+             <meta name=\"theme-color\" content=\"#000000\">\n</head>"
+        ));
+        assert!(injected.contains("register(\"sw.js\");\n        }\n    </script>\n</body>"));
+        assert_eq!(inject(&injected, true), injected);
+        assert!(!inject(html, false).contains("serviceWorker"));
+
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&[0, 0, 0, 192, 0, 0, 2, 0]);
+        assert_eq!(png_size(&png), Some((192, 512)));
+        assert_eq!(png_size(b"GIF89a"), None);
+    }
+}
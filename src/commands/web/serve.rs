@@ -0,0 +1,287 @@
+//! `plyx web --serve` — a static file server for `build/web`.
+//!
+//! Every response carries `Cache-Control: no-cache` so a rebuild is picked
+//! up on the next reload, and `.wasm` is served as `application/wasm` so
+//! browsers can stream-compile it. Binding to anything other than loopback
+//! prints the LAN URL with a QR code for testing on phones.
+
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use super::compress;
+use super::live::{self, LiveReload};
+
+/// Default `--port`.
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// Serve `root` at `base` (e.g. `/` or `/my-game/`) on `host:port` until
+/// the process is stopped. With `live`, pages get the live-reload script
+/// (see [`live`]).
+pub fn serve(
+    root: &Path,
+    base: &str,
+    host: &str,
+    port: u16,
+    live: Option<Arc<LiveReload>>,
+) -> Result<(), String> {
+    let server = Server::http((host, port)).map_err(|e| {
+        format!("Failed to listen on {host}:{port}: {e}. Try another --port.")
+    })?;
+    let addr = server
+        .server_addr()
+        .to_ip()
+        .ok_or("Server is not listening on an IP address")?;
+
+    println!("\nServing {} at:", root.display());
+    if addr.ip().is_unspecified() {
+        println!("  Local:   http://localhost:{}{base}", addr.port());
+    } else {
+        println!("  Local:   {}", url_for(addr.ip(), addr.port(), base));
+    }
+    if let Some(lan) = lan_address(addr.ip()) {
+        let url = url_for(lan, addr.port(), base);
+        println!("  Network: {url}\n");
+        if let Some(qr) = qr_code(&url) {
+            println!("{qr}");
+        }
+    } else if addr.ip().is_loopback() {
+        println!("  Use --host 0.0.0.0 to open it from other devices.");
+    }
+    println!("Press Ctrl-C to stop.");
+
+    let root = root.to_path_buf();
+    for request in server.incoming_requests() {
+        let (root, base) = (root.clone(), base.to_string());
+        let live = live.clone();
+        std::thread::spawn(move || handle(&root, &base, request, live.as_deref()));
+    }
+    Ok(())
+}
+
+fn handle(root: &Path, base: &str, request: Request, live: Option<&LiveReload>) {
+    if !matches!(request.method(), Method::Get | Method::Head) {
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(405));
+        return;
+    }
+    let head = *request.method() == Method::Head;
+
+    // Serve the build under its base path, like the real host will.
+    let url = request.url();
+    if base != "/" && (url == "/" || url == base.trim_end_matches('/')) {
+        let response = Response::empty(302).with_header(header("Location", base));
+        let _ = request.respond(response);
+        return;
+    }
+    let path = url.strip_prefix(base).map(|rest| format!("/{rest}"));
+    let mut file = path
+        .and_then(|path| resolve(root, &path))
+        .and_then(|path| fs::read(&path).ok().map(|d| (path, d)));
+    if let Some(live) = live {
+        let path = request.url().split('?').next().unwrap_or("");
+        match path {
+            live::EVENTS_PATH => return live.stream(request),
+            live::SCRIPT_PATH => {
+                file = Some((PathBuf::from("live.js"), live::SCRIPT.as_bytes().to_vec()));
+            }
+            _ if path == base && file.is_none() => {
+                file = Some((PathBuf::from("index.html"), live::WAITING_PAGE.as_bytes().to_vec()));
+            }
+            _ => {}
+        }
+        if let Some((path, data)) = &mut file {
+            if content_type(path).starts_with("text/html") {
+                *data = live::inject(&String::from_utf8_lossy(data)).into_bytes();
+            }
+        }
+    }
+
+    // Precompressed siblings from `--compress`, unless the page was just
+    // rewritten above.
+    let mut encoding = None;
+    if let Some((path, data)) = &mut file {
+        let rewritten = live.is_some() && content_type(path).starts_with("text/html");
+        let accept = request
+            .headers()
+            .iter()
+            .find(|h| h.field.equiv("Accept-Encoding"))
+            .map(|h| h.value.as_str().to_string())
+            .unwrap_or_default();
+        if !rewritten {
+            for (name, ext) in compress::negotiate(&accept) {
+                if let Some(compressed) = fresh_sibling(path, ext) {
+                    *data = compressed;
+                    encoding = Some(*name);
+                    break;
+                }
+            }
+        }
+    }
+
+    let response = match file {
+        Some((path, data)) => {
+            let len = data.len();
+            let mut response = Response::from_data(if head { Vec::new() } else { data })
+                .with_header(header("Content-Type", content_type(&path)))
+                .with_header(header("Vary", "Accept-Encoding"));
+            if let Some(encoding) = encoding {
+                response.add_header(header("Content-Encoding", encoding));
+            }
+            response
+                .with_header(header("Cache-Control", "no-cache, no-store, must-revalidate"))
+                .with_header(header("Content-Length", &len.to_string()))
+                .with_chunked_threshold(usize::MAX)
+        }
+        None => Response::from_data(b"Not found".to_vec())
+            .with_status_code(404)
+            .with_header(header("Content-Type", "text/plain; charset=utf-8")),
+    };
+    let _ = request.respond(response);
+}
+
+/// The contents of `path`'s `.<ext>` sibling, if it is at least as new as
+/// `path` itself.
+fn fresh_sibling(path: &Path, ext: &str) -> Option<Vec<u8>> {
+    let sibling = compress::sibling(path, ext);
+    let modified = |p: &Path| fs::metadata(p).and_then(|m| m.modified()).ok();
+    if modified(&sibling)? < modified(path)? {
+        return None;
+    }
+    fs::read(sibling).ok()
+}
+
+/// Map a request path to a file under `root`, refusing anything that
+/// would escape it. Directories serve their `index.html`.
+fn resolve(root: &Path, url: &str) -> Option<PathBuf> {
+    let path = url.split(['?', '#']).next().unwrap_or("");
+    let decoded = percent_decode(path)?;
+    let mut file = root.to_path_buf();
+    for component in Path::new(decoded.trim_start_matches('/')).components() {
+        match component {
+            Component::Normal(part) => file.push(part),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    file.is_file().then_some(file)
+}
+
+fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// MIME type by file extension.
+pub(crate) fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match ext.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "wasm" => "application/wasm",
+        "css" => "text/css; charset=utf-8",
+        "json" => "application/json",
+        "webmanifest" => "application/manifest+json",
+        "txt" | "glsl" | "vert" | "frag" => "text/plain; charset=utf-8",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "ico" => "image/x-icon",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "wav" => "audio/wav",
+        "ogg" => "audio/ogg",
+        "mp3" => "audio/mpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+pub(super) fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn url_for(ip: IpAddr, port: u16, base: &str) -> String {
+    match ip {
+        IpAddr::V6(v6) => format!("http://[{v6}]:{port}{base}"),
+        IpAddr::V4(v4) => format!("http://{v4}:{port}{base}"),
+    }
+}
+
+/// The address other devices reach us on, when bound beyond loopback.
+fn lan_address(bound: IpAddr) -> Option<IpAddr> {
+    if bound.is_loopback() {
+        return None;
+    }
+    if !bound.is_unspecified() {
+        return Some(bound);
+    }
+    // Connecting a UDP socket sends nothing; it just picks the interface
+    // that routes outwards.
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 168, 0, 1), 9)).ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_loopback() && !ip.is_unspecified()).then_some(ip)
+}
+
+/// `url` as a QR code drawn with half-block characters.
+fn qr_code(url: &str) -> Option<String> {
+    let code = QrCode::new(url.as_bytes()).ok()?;
+    Some(
+        code.render::<Dense1x2>()
+            .dark_color(Dense1x2::Light)
+            .light_color(Dense1x2::Dark)
+            .build(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve() {
+        let root = std::env::temp_dir().join("plyx_test_serve");
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("assets/fonts")).unwrap();
+        fs::write(root.join("index.html"), "<html>").unwrap();
+        fs::write(root.join("assets/fonts/open sans.ttf"), "").unwrap();
+
+        assert_eq!(resolve(&root, "/?v=2"), Some(root.join("index.html")));
+        assert_eq!(
+            resolve(&root, "/assets/fonts/open%20sans.ttf"),
+            Some(root.join("assets/fonts/open sans.ttf"))
+        );
+        assert_eq!(resolve(&root, "/../Cargo.toml"), None);
+        assert_eq!(resolve(&root, "/missing.wasm"), None);
+        assert_eq!(content_type(Path::new("app.wasm")), "application/wasm");
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,229 @@
+//! `plyx web --single-file` — the whole build as one HTML file, for
+//! sharing a demo by email, chat or an itch.io embed.
+//!
+//! The page's local scripts (`ply_bundle.js`, plugins) are inlined, local
+//! `src`/`href` attributes (such as the favicon) become `data:` URLs, and
+//! `app.wasm` and every asset are embedded as base64. A small script in
+//! `<head>` answers `fetch` and `XMLHttpRequest` for the embedded paths.
+//! That covers the wasm itself and everything the app loads by path:
+//! `FontAsset::Path` and other files read through miniquad's
+//! `fs_load_file`. Nothing touches the network, so the file works from
+//! `file://` too.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use base64::Engine;
+
+use super::fingerprint;
+use super::output::Output;
+use super::serve::content_type;
+use crate::net::human_size;
+
+/// Serves the embedded files. `{{FILES}}` maps build paths to base64.
+const LOADER_JS: &str = r#"    <script>
+        (() => {
+            const files = new Map(
+                Object.entries({{FILES}}).map(([path, data]) => [new URL(path, document.baseURI).href, data])
+            );
+            const read = (url) => {
+                const data = files.get(new URL(String(url), document.baseURI).href);
+                if (data === undefined) {
+                    return null;
+                }
+                const text = atob(data);
+                const bytes = new Uint8Array(text.length);
+                for (let i = 0; i < text.length; i++) {
+                    bytes[i] = text.charCodeAt(i);
+                }
+                return bytes;
+            };
+
+            const fetch = window.fetch;
+            window.fetch = (url, ...rest) => {
+                const bytes = read(url instanceof Request ? url.url : url);
+                if (!bytes) {
+                    return fetch(url, ...rest);
+                }
+                const type = String(url).endsWith(".wasm") ? "application/wasm" : "application/octet-stream";
+                return Promise.resolve(new Response(bytes, { headers: { "Content-Type": type } }));
+            };
+
+            window.XMLHttpRequest = class extends XMLHttpRequest {
+                open(method, url, ...rest) {
+                    this.embedded = String(method).toUpperCase() === "GET" ? read(url) : null;
+                    super.open(method, url, ...rest);
+                }
+                send(body) {
+                    const bytes = this.embedded;
+                    if (!bytes) {
+                        return super.send(body);
+                    }
+                    const response = this.responseType === "arraybuffer"
+                        ? bytes.buffer
+                        : this.responseType === "blob" ? new Blob([bytes]) : new TextDecoder().decode(bytes);
+                    Object.defineProperties(this, {
+                        readyState: { value: 4 },
+                        status: { value: 200 },
+                        statusText: { value: "OK" },
+                        response: { value: response },
+                        responseText: { get: () => new TextDecoder().decode(bytes) },
+                    });
+                    setTimeout(() => {
+                        this.dispatchEvent(new Event("readystatechange"));
+                        for (const type of ["load", "loadend"]) {
+                            this.dispatchEvent(new ProgressEvent(type, { loaded: bytes.length, total: bytes.length }));
+                        }
+                    });
+                }
+            };
+        })();
+    </script>
+"#;
+
+/// Write `<crate>.html` into `out` from the built `html` and the files
+/// already in `out`.
+pub fn write(out: &mut Output, html: &str, crate_name: &str) -> Result<(), String> {
+    let mut files = BTreeMap::new();
+    for rel in out.files() {
+        let name: Vec<_> = rel.iter().map(|p| p.to_string_lossy()).collect();
+        files.insert(name.join("/"), out.path(rel));
+    }
+    files.remove("index.html");
+    files.remove(fingerprint::MANIFEST);
+
+    let html = data_urls(html, &files)?;
+    let (html, inlined) = inline_scripts(&html, &files)?;
+    let mut embedded = BTreeMap::new();
+    for (name, path) in &files {
+        if !inlined.contains(name) {
+            embedded.insert(name.as_str(), base64::engine::general_purpose::STANDARD.encode(read(path)?));
+        }
+    }
+    let json = serde_json::to_string(&embedded)
+        .map_err(|e| format!("Failed to encode the embedded files: {e}"))?
+        .replace('<', "\\u003c");
+    let loader = LOADER_JS.replace("{{FILES}}", &json);
+    let html = match html.find("</head>") {
+        Some(i) => format!("{}{loader}{}", &html[..i], &html[i..]),
+        None => return Err("index.html has no </head> to put the file loader in.".to_string()),
+    };
+
+    let name = format!("{crate_name}.html");
+    out.write(&name, html.as_bytes())?;
+    println!("  Wrote {name} ({}, single file)", human_size(html.len() as u64));
+    Ok(())
+}
+
+/// Replace `<script src="…"></script>` tags for files in `files` with the
+/// scripts themselves. Returns the page and the names inlined.
+fn inline_scripts(
+    html: &str,
+    files: &BTreeMap<String, std::path::PathBuf>,
+) -> Result<(String, Vec<String>), String> {
+    let mut out = String::with_capacity(html.len());
+    let mut inlined = Vec::new();
+    let mut rest = html;
+    while let Some(start) = rest.find("<script") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let open_end = rest.find('>').map_or(rest.len(), |i| i + 1);
+        let open = &rest[..open_end];
+        let name = attribute(open, "src").map(local_name);
+        let empty = rest[open_end..].starts_with("</script>");
+        match name.filter(|n| empty && files.contains_key(*n)) {
+            Some(name) => {
+                let script = String::from_utf8(read(&files[name])?)
+                    .map_err(|_| format!("{name} isn't UTF-8 text"))?;
+                // Only `</script` can end an inline script early.
+                out.push_str(&format!("<script>{}</script>", script.replace("</script", "<\\/script")));
+                inlined.push(name.to_string());
+                rest = &rest[open_end + "</script>".len()..];
+            }
+            None => {
+                out.push_str(open);
+                rest = &rest[open_end..];
+            }
+        }
+    }
+    out.push_str(rest);
+    Ok((out, inlined))
+}
+
+/// Point other tags' `src` and `href` attributes for files in `files` at
+/// `data:` URLs.
+fn data_urls(html: &str, files: &BTreeMap<String, std::path::PathBuf>) -> Result<String, String> {
+    let mut out = String::with_capacity(html.len());
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+        let mut tag = rest[..end].to_string();
+        if !tag.starts_with("<script") {
+            for attr in ["src", "href"] {
+                let Some(value) = attribute(&tag, attr).map(str::to_string) else { continue };
+                let Some(path) = files.get(local_name(&value)) else { continue };
+                let data = base64::engine::general_purpose::STANDARD.encode(read(path)?);
+                let url = format!("data:{};base64,{data}", content_type(path).replace(' ', ""));
+                tag = tag.replacen(&value, &url, 1);
+            }
+        }
+        out.push_str(&tag);
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+/// The value of `name="…"` (or `'…'`) in an opening tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    for quote in ['"', '\''] {
+        let key = format!(" {name}={quote}");
+        if let Some(start) = tag.find(&key).map(|i| i + key.len()) {
+            let len = tag[start..].find(quote)?;
+            return Some(&tag[start..start + len]);
+        }
+    }
+    None
+}
+
+/// `./app.wasm` → `app.wasm`.
+fn local_name(url: &str) -> &str {
+    url.strip_prefix("./").unwrap_or(url)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, String> {
+    fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline() {
+        let dir = std::env::temp_dir().join(format!("plyx-single-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("ply_bundle.js"), "if (a</script>b) {}").unwrap();
+        fs::write(dir.join("icon.svg"), "<svg/>").unwrap();
+        let files = BTreeMap::from([
+            ("ply_bundle.js".to_string(), dir.join("ply_bundle.js")),
+            ("icon.svg".to_string(), dir.join("icon.svg")),
+        ]);
+
+        let html = "<link rel=\"icon\" href=\"./icon.svg\">\n<script src=\"ply_bundle.js\"></script>\n\
+                    <script src=\"https://cdn/x.js\"></script><script>load();</script>";
+        let html = data_urls(html, &files).unwrap();
+        let (html, inlined) = inline_scripts(&html, &files).unwrap();
+        assert_eq!(
+            html,
+            "<link rel=\"icon\" href=\"data:image/svg+xml;base64,PHN2Zy8+\">\n\
+             <script>if (a<\\/script>b) {}</script>\n\
+             <script src=\"https://cdn/x.js\"></script><script>load();</script>"
+        );
+        assert_eq!(inlined, ["ply_bundle.js"]);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,165 @@
+//! `plyx web --watch` — rebuild on changes and reload the browser.
+//!
+//! Changes in `src/` or `shaders/` rebuild the wasm. Changes in `assets/`,
+//! `web/`, `index.html` or `ply_bundle.js` only resync the output directory. Either
+//! way, connected pages reload, or show cargo's errors if the build failed.
+
+use std::collections::BTreeSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+
+use notify::{RecursiveMode, Watcher};
+
+use super::live::LiveReload;
+use super::{Options, Project};
+
+/// Paths watched, relative to the project root.
+const WATCHED: &[(&str, RecursiveMode)] = &[
+    ("src", RecursiveMode::Recursive),
+    ("shaders", RecursiveMode::Recursive),
+    ("assets", RecursiveMode::Recursive),
+    ("web", RecursiveMode::Recursive),
+    // The root itself, for index.html and ply_bundle.js: editors often
+    // replace a file rather than write to it, which a watch on the file
+    // would lose.
+    (".", RecursiveMode::NonRecursive),
+];
+
+/// How long the tree has to be quiet before acting, so a save that touches
+/// several files triggers one build.
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// What a changed path calls for, cheapest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Change {
+    /// Assets, plugins, `index.html` or a bundle override: resync the
+    /// output.
+    Copy,
+    Rebuild,
+}
+
+pub(super) fn run(project: &Project, opts: &Options) -> Result<(), String> {
+    let live = Arc::new(LiveReload::default());
+    if let Err(e) = super::build(project, true, opts.clean) {
+        eprintln!("\nBuild failed; fix it and save to try again.");
+        live.fail(e);
+    }
+    let out = &project.out_dir;
+    std::fs::create_dir_all(out).map_err(|e| format!("Failed to create {}: {e}", out.display()))?;
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("Failed to watch files: {e}"))?;
+    for (path, mode) in WATCHED {
+        if Path::new(path).exists() {
+            watcher
+                .watch(Path::new(path), *mode)
+                .map_err(|e| format!("Failed to watch {path}: {e}"))?;
+        }
+    }
+
+    let root = std::env::current_dir().map_err(|e| format!("Failed to read current dir: {e}"))?;
+    let builder = project.clone();
+    let builds = live.clone();
+    std::thread::spawn(move || {
+        // Keep the watcher alive for as long as events are read.
+        let _watcher = watcher;
+        while let Some(change) = next_change(&rx, &root) {
+            if let Err(e) = apply(&builder, change, &builds) {
+                eprintln!("Error: {e}");
+            }
+        }
+    });
+    super::serve::serve(out, &project.base_path, &opts.host, opts.port, Some(live))
+}
+
+/// Wait for changes, then for the tree to go quiet; returns the most
+/// expensive change seen, or `None` once the watcher is gone.
+fn next_change(rx: &mpsc::Receiver<notify::Result<notify::Event>>, root: &Path) -> Option<Change> {
+    let mut changes = BTreeSet::new();
+    loop {
+        let event = if changes.is_empty() {
+            rx.recv().ok()?
+        } else {
+            match rx.recv_timeout(DEBOUNCE) {
+                Ok(event) => event,
+                Err(mpsc::RecvTimeoutError::Timeout) => return changes.pop_last(),
+                Err(mpsc::RecvTimeoutError::Disconnected) => return None,
+            }
+        };
+        let Ok(event) = event else { continue };
+        if event.kind.is_access() {
+            continue;
+        }
+        changes.extend(event.paths.iter().filter_map(|p| classify(p, root)));
+    }
+}
+
+/// What a change to `path` calls for, if anything.
+fn classify(path: &Path, root: &Path) -> Option<Change> {
+    let rel: PathBuf = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect();
+    let name = rel.file_name()?.to_str()?;
+    // Editor swap and backup files.
+    if name.starts_with(".#") || name.ends_with('~') || name.ends_with(".swp") {
+        return None;
+    }
+    if rel.starts_with("src") || rel.starts_with("shaders") {
+        Some(Change::Rebuild)
+    } else if rel.starts_with("assets")
+        || rel.starts_with("web")
+        || rel == Path::new("index.html")
+        || rel == Path::new(super::bundle::OVERRIDE)
+    {
+        Some(Change::Copy)
+    } else {
+        None
+    }
+}
+
+fn apply(project: &Project, change: Change, live: &LiveReload) -> Result<(), String> {
+    println!();
+    match change {
+        Change::Rebuild => {
+            println!("Source changed, rebuilding...");
+            if let Err(e) = super::build(project, true, false) {
+                eprintln!("\nBuild failed; fix it and save to try again.");
+                live.fail(e);
+                return Ok(());
+            }
+        }
+        Change::Copy => super::assemble(project, false)?,
+    }
+    if change != Change::Rebuild && live.is_failing() {
+        // Keep showing the build error; a reload would hide it.
+        return Ok(());
+    }
+    println!("Reloading browsers.");
+    live.reload();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let root = Path::new("/p");
+        assert_eq!(classify(Path::new("/p/src/ui/menu.rs"), root), Some(Change::Rebuild));
+        assert_eq!(classify(Path::new("/p/shaders/glow.frag"), root), Some(Change::Rebuild));
+        assert_eq!(classify(Path::new("/p/assets/fonts/lexend.ttf"), root), Some(Change::Copy));
+        assert_eq!(classify(Path::new("/p/index.html"), root), Some(Change::Copy));
+        assert_eq!(classify(Path::new("./index.html"), root), Some(Change::Copy));
+        assert_eq!(classify(Path::new("/p/ply_bundle.js"), root), Some(Change::Copy));
+        assert_eq!(classify(Path::new("/p/web/plugins/gamepad.js"), root), Some(Change::Copy));
+        assert_eq!(classify(Path::new("/p/src/.main.rs.swp"), root), None);
+        assert_eq!(classify(Path::new("/p/build/web/app.wasm"), root), None);
+    }
+}
@@ -0,0 +1,61 @@
+//! Project-level plyx settings.
+//!
+//! Settings live in the project's own Cargo.toml, next to the
+//! `[package.metadata.android]` table plyx already uses:
+//!
+//! ```toml
+//! [package.metadata.plyx]
+//! font-source = "mirror:/srv/fonts"
+//! ```
+//!
+//! Missing files, tables or keys are not errors — callers fall back to
+//! their defaults.
+
+use std::fs;
+
+/// The `[package.metadata.plyx]` table of `./Cargo.toml`, if any.
+pub(crate) fn metadata() -> Option<toml_edit::Table> {
+    let cargo_str = fs::read_to_string("Cargo.toml").ok()?;
+    let doc: toml_edit::DocumentMut = cargo_str.parse().ok()?;
+    let plyx = doc.get("package")?.get("metadata")?.get("plyx")?;
+    match plyx {
+        toml_edit::Item::Table(t) => Some(t.clone()),
+        toml_edit::Item::Value(toml_edit::Value::InlineTable(t)) => {
+            Some(t.clone().into_table())
+        }
+        _ => None,
+    }
+}
+
+/// A string setting from `[package.metadata.plyx]`, e.g. `"font-source"`.
+/// Dotted keys descend into sub-tables.
+pub(crate) fn get_str(key: &str) -> Option<String> {
+    lookup(key, |item| item.as_str().map(|s| s.to_string()))
+}
+
+/// A boolean setting from `[package.metadata.plyx]`.
+pub(crate) fn get_bool(key: &str) -> Option<bool> {
+    lookup(key, |item| item.as_bool())
+}
+
+/// A list setting from `[package.metadata.plyx]`: an array of strings, or a
+/// single string as a one-item list.
+pub(crate) fn get_list(key: &str) -> Option<Vec<String>> {
+    lookup(key, |item| {
+        if let Some(s) = item.as_str() {
+            return Some(vec![s.to_string()]);
+        }
+        let list = item.as_array()?.iter().filter_map(|v| v.as_str());
+        Some(list.map(str::to_string).collect())
+    })
+}
+
+/// Find `key` (dotted) in `[package.metadata.plyx]` and read it with `read`.
+fn lookup<T>(key: &str, read: impl FnOnce(&toml_edit::Item) -> Option<T>) -> Option<T> {
+    let table = metadata()?;
+    let mut item: &toml_edit::Item = table.get(key.split('.').next()?)?;
+    for part in key.split('.').skip(1) {
+        item = item.get(part)?;
+    }
+    read(item)
+}
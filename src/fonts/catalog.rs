@@ -0,0 +1,261 @@
+//! Catalog entries and search filters.
+//!
+//! The catalog (`fontlist-v2.json`, or a mirror's `fontlist.json`) is a
+//! popularity-sorted JSON array. Each entry is either a bare family name
+//! (the original format) or an object with metadata:
+//!
+//! ```json
+//! { "family": "Fira Code", "category": "mono",
+//!   "subsets": ["latin", "cyrillic", "greek"],
+//!   "weights": [300, 400, 500, 600, 700], "variable": true }
+//! ```
+//!
+//! Both forms may be mixed; a bare name simply has no metadata. Search
+//! filters only exist for a catalog that has some (see
+//! [`FontQuery::for_catalog`]).
+
+use serde::{Deserialize, Serialize};
+
+/// Google Fonts design category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Serif,
+    #[serde(alias = "sans-serif", alias = "sans serif")]
+    Sans,
+    #[serde(alias = "monospace")]
+    Mono,
+    Display,
+    Handwriting,
+}
+
+impl Category {
+    pub const ALL: &[Category] = &[
+        Category::Serif,
+        Category::Sans,
+        Category::Mono,
+        Category::Display,
+        Category::Handwriting,
+    ];
+
+    /// Short name used in the catalog and in `mono:`-style filters.
+    pub fn key(self) -> &'static str {
+        match self {
+            Category::Serif => "serif",
+            Category::Sans => "sans",
+            Category::Mono => "mono",
+            Category::Display => "display",
+            Category::Handwriting => "handwriting",
+        }
+    }
+}
+
+/// One font family in the catalog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "CatalogEntry")]
+pub struct FontInfo {
+    pub family: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<Category>,
+    /// Supported scripts / subsets, e.g. `latin`, `cyrillic`, `greek-ext`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub subsets: Vec<String>,
+    /// Available upright weights, e.g. `[400, 700]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub weights: Vec<u16>,
+    /// Whether a variable version of the family exists.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub variable: bool,
+}
+
+impl FontInfo {
+    /// An entry with a name and no metadata.
+    pub fn named(family: &str) -> Self {
+        FontInfo {
+            family: family.to_string(),
+            category: None,
+            subsets: Vec::new(),
+            weights: Vec::new(),
+            variable: false,
+        }
+    }
+}
+
+/// On-disk shape: a bare name or a full object.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum CatalogEntry {
+    Name(String),
+    Full {
+        family: String,
+        #[serde(default)]
+        category: Option<Category>,
+        #[serde(default)]
+        subsets: Vec<String>,
+        #[serde(default)]
+        weights: Vec<u16>,
+        #[serde(default)]
+        variable: bool,
+    },
+}
+
+impl From<CatalogEntry> for FontInfo {
+    fn from(entry: CatalogEntry) -> Self {
+        match entry {
+            CatalogEntry::Name(family) => FontInfo::named(&family),
+            CatalogEntry::Full { family, category, subsets, weights, variable } => FontInfo {
+                family,
+                category,
+                subsets,
+                weights,
+                variable,
+            },
+        }
+    }
+}
+
+/// Parse a catalog in either format.
+pub fn parse(json: &str) -> Result<Vec<FontInfo>, String> {
+    serde_json::from_str(json).map_err(|e| e.to_string())
+}
+
+/// Whether any entry has a category or subsets to filter on.
+pub fn has_metadata(fonts: &[FontInfo]) -> bool {
+    fonts.iter().any(|f| f.category.is_some() || !f.subsets.is_empty())
+}
+
+// ── Filters ─────────────────────────────────────────────────────────────
+
+/// A single metadata filter from a search query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    /// `mono:`, `serif:`, `sans:`, `display:`, `handwriting:`
+    Category(Category),
+    /// `script:cyrillic` (prefix match on subsets, so `script:greek` also
+    /// matches `greek-ext`)
+    Script(String),
+    /// `weight:700`
+    Weight(u16),
+    /// `variable:`
+    Variable,
+}
+
+/// A search query split into free text and metadata filters.
+///
+/// Filters are `key:` or `key:value` tokens anywhere in the query, e.g.
+/// `mono: fira` or `script:cyrillic sans:`. Unknown `key:` tokens are kept
+/// as text.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FontQuery {
+    /// The remaining free text, lowercased and trimmed.
+    pub text: String,
+    filters: Vec<Filter>,
+}
+
+impl FontQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut words: Vec<&str> = Vec::new();
+        let mut filters = Vec::new();
+
+        for token in query.split_whitespace() {
+            match parse_filter(token) {
+                Some(f) => filters.push(f),
+                None => words.push(token),
+            }
+        }
+
+        FontQuery {
+            text: words.join(" ").to_lowercase(),
+            filters,
+        }
+    }
+
+    /// Parse `query` for searching `fonts`. Without metadata in the
+    /// catalog there is nothing to filter on, so `key:` tokens stay text.
+    pub fn for_catalog(query: &str, fonts: &[FontInfo]) -> Self {
+        if has_metadata(fonts) {
+            return Self::parse(query);
+        }
+        FontQuery {
+            text: query.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+            filters: Vec::new(),
+        }
+    }
+
+    pub fn has_filters(&self) -> bool {
+        !self.filters.is_empty()
+    }
+
+    /// Whether `info` passes every filter. Entries without metadata never
+    /// pass a filter.
+    pub fn matches_filters(&self, info: Option<&FontInfo>) -> bool {
+        if self.filters.is_empty() {
+            return true;
+        }
+        let Some(info) = info else {
+            return false;
+        };
+        self.filters.iter().all(|f| match f {
+            Filter::Category(c) => info.category == Some(*c),
+            Filter::Script(s) => info.subsets.iter().any(|sub| sub.starts_with(s.as_str())),
+            Filter::Weight(w) => info.weights.contains(w),
+            Filter::Variable => info.variable,
+        })
+    }
+}
+
+fn parse_filter(token: &str) -> Option<Filter> {
+    let (key, value) = token.split_once(':')?;
+    let key = key.to_lowercase();
+    let value = value.trim().to_lowercase();
+
+    if let Some(c) = Category::ALL.iter().find(|c| c.key() == key) {
+        return value.is_empty().then_some(Filter::Category(*c));
+    }
+    match key.as_str() {
+        "script" | "subset" if !value.is_empty() => Some(Filter::Script(value)),
+        "weight" => value.parse().ok().map(Filter::Weight),
+        "variable" if value.is_empty() => Some(Filter::Variable),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mixed_catalog() {
+        let fonts = parse(
+            r#"["Roboto", {"family": "Fira Code", "category": "monospace",
+                "subsets": ["latin", "cyrillic-ext"], "weights": [400, 700], "variable": true}]"#,
+        )
+        .unwrap();
+        assert_eq!(fonts[0], FontInfo::named("Roboto"));
+        assert_eq!(fonts[1].category, Some(Category::Mono));
+        assert!(fonts[1].variable);
+    }
+
+    #[test]
+    fn test_query_filters() {
+        let fira = FontInfo {
+            category: Some(Category::Mono),
+            subsets: vec!["latin".into(), "cyrillic-ext".into()],
+            weights: vec![400, 700],
+            ..FontInfo::named("Fira Code")
+        };
+
+        let q = FontQuery::parse("mono: Fira script:cyrillic");
+        assert_eq!(q.text, "fira");
+        assert!(q.matches_filters(Some(&fira)));
+        assert!(!q.matches_filters(None));
+
+        assert!(!FontQuery::parse("serif:").matches_filters(Some(&fira)));
+        assert!(!FontQuery::parse("weight:900").matches_filters(Some(&fira)));
+        assert_eq!(FontQuery::parse("foo:bar").text, "foo:bar");
+
+        let plain = [FontInfo::named("Fira Code")];
+        assert!(!FontQuery::for_catalog("mono: fira", &plain).has_filters());
+        assert!(FontQuery::for_catalog("mono: fira", &[fira]).has_filters());
+    }
+}
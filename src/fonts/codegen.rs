@@ -0,0 +1,198 @@
+//! Generated `src/fonts.rs` — one typed `FontAsset` per installed font.
+//!
+//! Regenerated whenever plyx adds or removes a font, so a font referenced as
+//! `fonts::OPEN_SANS_REGULAR` that no longer exists is a compile error instead
+//! of a runtime load failure. A `src/fonts.rs` that plyx didn't generate is
+//! never overwritten.
+
+use std::fs;
+use std::path::Path;
+
+/// First line of the generated file; marks it as safe to overwrite.
+pub(crate) const GENERATED_MARKER: &str = "// @generated by plyx";
+
+/// Weight/style words recognised in font file names.
+const STYLE_WORDS: &[&str] = &[
+    "thin", "hairline", "extralight", "ultralight", "light", "regular", "normal", "book",
+    "medium", "semibold", "demibold", "bold", "extrabold", "ultrabold", "black", "heavy",
+    "italic", "oblique",
+];
+
+/// One `pub static` in the generated module.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontConst {
+    /// `SCREAMING_SNAKE_CASE` name, e.g. `OPEN_SANS_REGULAR`.
+    pub ident: String,
+    /// Human-readable description for the doc comment.
+    pub label: String,
+    /// Path relative to the project root, e.g. `assets/fonts/open_sans.ttf`.
+    pub path: String,
+}
+
+/// Build the constant for a font file in `assets/fonts/`.
+pub fn font_const(file: &str) -> FontConst {
+    let stem = Path::new(file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file);
+
+    let words: Vec<String> = stem
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| w.to_lowercase())
+        .collect();
+    let has_style = words.iter().any(|w| is_style_word(w));
+
+    let mut ident = words.join("_").to_uppercase();
+    if !has_style {
+        ident.push_str("_REGULAR");
+    }
+    if ident.starts_with(|c: char| c.is_ascii_digit()) || ident.is_empty() {
+        ident.insert_str(0, "FONT_");
+    }
+
+    let mut label = super::family_from_filename(stem);
+    if !has_style {
+        label.push_str(" Regular");
+    }
+
+    FontConst {
+        ident,
+        label,
+        path: format!("assets/fonts/{file}"),
+    }
+}
+
+/// Render the module source for the given constants.
+pub fn render_module(consts: &[FontConst]) -> String {
+    let mut out = format!(
+        "{GENERATED_MARKER} from assets/fonts/ — do not edit.\n\
+         // Regenerated by plyx whenever fonts are added or removed.\n\
+         #![allow(dead_code)]\n\n\
+         use ply_engine::prelude::FontAsset;\n"
+    );
+
+    for c in consts {
+        out.push_str(&format!(
+            "\n/// {} (`{}`)\npub static {}: FontAsset = FontAsset::Path({:?});\n",
+            doc_text(&c.label),
+            doc_text(&c.path),
+            c.ident,
+            c.path
+        ));
+    }
+
+    out.push_str("\n/// Every font in assets/fonts/.\npub static ALL: &[&FontAsset] = &[");
+    if !consts.is_empty() {
+        out.push('\n');
+        for c in consts {
+            out.push_str(&format!("    &{},\n", c.ident));
+        }
+    }
+    out.push_str("];\n");
+    out
+}
+
+/// Rewrite `<project_root>/src/fonts.rs` from `<project_root>/assets/fonts/`.
+///
+/// Skips (with a warning) a hand-written `src/fonts.rs`.
+pub fn regenerate(project_root: &Path) -> Result<(), String> {
+    let module_path = project_root.join("src/fonts.rs");
+    if let Ok(existing) = fs::read_to_string(&module_path) {
+        if !existing.starts_with(GENERATED_MARKER) {
+            eprintln!(
+                "Warning: {} wasn't generated by plyx; not updating it.",
+                module_path.display()
+            );
+            return Ok(());
+        }
+    }
+
+    let source = render_module(&installed_consts(project_root));
+    if fs::read_to_string(&module_path).ok().as_deref() == Some(source.as_str()) {
+        return Ok(());
+    }
+    fs::write(&module_path, source)
+        .map_err(|e| format!("Failed to write {}: {e}", module_path.display()))?;
+    println!("  Updated src/fonts.rs");
+
+    let main_rs = fs::read_to_string(project_root.join("src/main.rs")).unwrap_or_default();
+    if !main_rs.contains("mod fonts;") {
+        println!("  Tip: add `mod fonts;` to src/main.rs to use the generated font constants.");
+    }
+    Ok(())
+}
+
+/// The constants for the fonts in `<project_root>/assets/fonts/`, as they
+/// appear in the generated module.
+pub fn installed_consts(project_root: &Path) -> Vec<FontConst> {
+    let mut consts: Vec<FontConst> = super::metadata::installed(&project_root.join("assets/fonts"))
+        .iter()
+        .map(|font| {
+            let mut c = font_const(&font.file);
+            if let Some(meta) = &font.meta {
+                c.label = format!("{} {}", meta.family, meta.subfamily);
+            }
+            c
+        })
+        .collect();
+    dedupe_idents(&mut consts);
+    consts
+}
+
+/// `text` made safe for a `///` line: control characters (line breaks
+/// included) become spaces.
+pub(crate) fn doc_text(text: &str) -> String {
+    text.chars().map(|c| if c.is_control() { ' ' } else { c }).collect()
+}
+
+/// Whether `word` is made only of style words ("bold", "bolditalic", …).
+fn is_style_word(word: &str) -> bool {
+    word.is_empty()
+        || STYLE_WORDS
+            .iter()
+            .any(|s| word.strip_prefix(s).is_some_and(is_style_word))
+}
+
+/// Suffix clashing identifiers with `_2`, `_3`, ….
+fn dedupe_idents(consts: &mut [FontConst]) {
+    for i in 1..consts.len() {
+        let mut n = 2;
+        let base = consts[i].ident.clone();
+        while consts[..i].iter().any(|c| c.ident == consts[i].ident) {
+            consts[i].ident = format!("{base}_{n}");
+            n += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_font_const_names() {
+        let c = font_const("open_sans.ttf");
+        assert_eq!(c.ident, "OPEN_SANS_REGULAR");
+        assert_eq!(c.label, "Open Sans Regular");
+        assert_eq!(c.path, "assets/fonts/open_sans.ttf");
+
+        assert_eq!(font_const("Inter-BoldItalic.ttf").ident, "INTER_BOLDITALIC");
+        assert_eq!(font_const("Roboto-Bold.ttf").ident, "ROBOTO_BOLD");
+        assert_eq!(font_const("42dot_sans.ttf").ident, "FONT_42DOT_SANS_REGULAR");
+
+        let src = render_module(&[c]);
+        assert!(src.starts_with(GENERATED_MARKER));
+        assert!(src.contains(
+            "pub static OPEN_SANS_REGULAR: FontAsset = FontAsset::Path(\"assets/fonts/open_sans.ttf\");"
+        ));
+
+        let odd = FontConst {
+            label: "Evil\npub static X: u8 = 0;".to_string(),
+            ..font_const("a\"b\\c.ttf")
+        };
+        let src = render_module(&[odd]);
+        assert!(src.contains("/// Evil pub static X: u8 = 0; (`assets/fonts/a\"b\\c.ttf`)\n"));
+        assert!(src.contains("FontAsset::Path(\"assets/fonts/a\\\"b\\\\c.ttf\");"));
+    }
+}
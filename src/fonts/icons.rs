@@ -0,0 +1,311 @@
+//! Icon fonts — Material Symbols with generated glyph constants.
+//!
+//! `plyx add icons material-symbols` downloads the icon font and its
+//! `.codepoints` list (one `name hex` pair per line) and writes
+//! `src/icons.rs`:
+//!
+//! ```ignore
+//! pub static FONT: FontAsset = FontAsset::Path("assets/fonts/material_symbols_outlined.ttf");
+//! pub const SETTINGS: &str = "\u{e8b8}";
+//! ```
+//!
+//! Naming icons subsets the font (through the Google Fonts `icon_names`
+//! parameter) and the module to those icons plus any `icons::…` already
+//! used in `src/`, so unused glyphs don't ship.
+//!
+//! A mirror serves `<root>/icons/<file stem>.ttf` and `.codepoints`; it
+//! can't subset, so it always provides the full font.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::license::{self, LicenseRecord};
+use super::source::{self, FontSource, MAX_FONT_BYTES};
+use super::{codegen, lock};
+use crate::net;
+
+const MATERIAL_REPO_URL: &str =
+    "https://raw.githubusercontent.com/google/material-design-icons/master";
+
+/// Material Symbols styles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Outlined,
+    Rounded,
+    Sharp,
+}
+
+impl Style {
+    pub const ALL: [Style; 3] = [Style::Outlined, Style::Rounded, Style::Sharp];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Style::Outlined => "outlined",
+            Style::Rounded => "rounded",
+            Style::Sharp => "sharp",
+        }
+    }
+
+    /// Google Fonts family, e.g. "Material Symbols Rounded".
+    pub fn family(self) -> String {
+        let key = self.key();
+        format!("Material Symbols {}{}", key[..1].to_uppercase(), &key[1..])
+    }
+
+    /// Variable-font file stem in the material-design-icons repo.
+    fn repo_stem(self) -> String {
+        format!(
+            "MaterialSymbols{}[FILL,GRAD,opsz,wght]",
+            self.family().trim_start_matches("Material Symbols ")
+        )
+    }
+
+    /// Parse `material-symbols[-style]`.
+    pub fn parse(set: &str) -> Option<Self> {
+        let set = set.to_lowercase();
+        let style = set.strip_prefix("material-symbols")?;
+        match style.trim_start_matches('-') {
+            "" => Some(Style::Outlined),
+            s => Style::ALL.into_iter().find(|st| st.key() == s),
+        }
+    }
+}
+
+/// Parse a `.codepoints` file into `name → codepoint`.
+pub fn parse_codepoints(text: &str) -> BTreeMap<String, u32> {
+    text.lines()
+        .filter_map(|line| {
+            let (name, hex) = line.trim().split_once(' ')?;
+            Some((name.to_string(), u32::from_str_radix(hex.trim(), 16).ok()?))
+        })
+        .collect()
+}
+
+/// Constant name for an icon: "arrow_back" → `ARROW_BACK`, "10k" → `ICON_10K`.
+pub fn const_name(icon: &str) -> String {
+    let upper = icon.to_uppercase().replace(|c: char| !c.is_ascii_alphanumeric(), "_");
+    if upper.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("ICON_{upper}")
+    } else {
+        upper
+    }
+}
+
+/// Render `src/icons.rs`.
+pub fn render_module(style: Style, font_file: &str, icons: &BTreeMap<String, u32>) -> String {
+    let mut out = format!(
+        "{} from {} — do not edit.\n\
+         // Regenerated by `plyx add icons`.\n\
+         #![allow(dead_code)]\n\n\
+         use ply_engine::prelude::FontAsset;\n\n\
+         /// {} icon font. Draw the constants below with it.\n\
+         pub static FONT: FontAsset = FontAsset::Path({:?});\n",
+        codegen::GENERATED_MARKER,
+        style.family(),
+        style.family(),
+        format!("assets/fonts/{font_file}"),
+    );
+    let mut seen = std::collections::HashSet::new();
+    for (name, cp) in icons {
+        let ident = const_name(name);
+        if seen.insert(ident.clone()) {
+            out.push_str(&format!(
+                "\n/// `{}`\npub const {ident}: &str = \"\\u{{{cp:x}}}\";\n",
+                codegen::doc_text(name)
+            ));
+        }
+    }
+    out
+}
+
+/// Icon names referenced as `icons::NAME` in the project's Rust sources.
+fn used_icons(src_dir: &Path, icons: &BTreeMap<String, u32>) -> Vec<String> {
+    let by_const: BTreeMap<String, &String> =
+        icons.keys().map(|name| (const_name(name), name)).collect();
+    let mut used = Vec::new();
+    let mut stack = vec![src_dir.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for path in entries.flatten().map(|e| e.path()) {
+            if path.is_dir() {
+                stack.push(path);
+                continue;
+            }
+            if path.extension().and_then(|e| e.to_str()) != Some("rs")
+                || path.ends_with("icons.rs")
+            {
+                continue;
+            }
+            let text = fs::read_to_string(&path).unwrap_or_default();
+            for (i, prefix) in text.match_indices("icons::") {
+                let rest = &text[i + prefix.len()..];
+                let ident: String = rest
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric() || *c == '_')
+                    .collect();
+                if let Some(name) = by_const.get(&ident) {
+                    used.push((*name).clone());
+                }
+            }
+        }
+    }
+    used.sort();
+    used.dedup();
+    used
+}
+
+/// Download the icon font (subset to `names` plus icons used in `src/`, if
+/// any names are given) and write `src/icons.rs`.
+pub fn add(style: Style, names: &[String], project_root: &Path) -> Result<(), String> {
+    let source = FontSource::configured()?;
+    if source.is_remote() {
+        net::require_online(&format!("Downloading {}", style.family()))?;
+    }
+
+    let stem = super::font_filename(&style.family()).trim_end_matches(".ttf").to_string();
+    let (font_base, codepoints_url) = match &source {
+        FontSource::Mirror { root } => {
+            let base = format!("{root}/icons/{stem}");
+            (base.clone(), format!("{base}.codepoints"))
+        }
+        _ => {
+            let base = format!("{MATERIAL_REPO_URL}/variablefont/{}", url_escape(&style.repo_stem()));
+            (base.clone(), format!("{base}.codepoints"))
+        }
+    };
+
+    println!("Fetching {} codepoints...", style.family());
+    let codepoints = source::fetch(&codepoints_url, MAX_FONT_BYTES)
+        .map_err(|e| format!("Failed to fetch icon codepoints: {e}"))?;
+    let all = parse_codepoints(&String::from_utf8_lossy(&codepoints));
+    if all.is_empty() {
+        return Err(format!("{codepoints_url} lists no icons."));
+    }
+
+    // Pick the icons to keep.
+    let icons: BTreeMap<String, u32> = if names.is_empty() {
+        all.clone()
+    } else {
+        let mut wanted: Vec<String> = names.iter().map(|n| n.to_lowercase()).collect();
+        let unknown: Vec<&String> = wanted.iter().filter(|n| !all.contains_key(*n)).collect();
+        if !unknown.is_empty() {
+            let list: Vec<&str> = unknown.iter().map(|s| s.as_str()).collect();
+            return Err(format!("Unknown icon(s): {}", list.join(", ")));
+        }
+        wanted.extend(used_icons(&project_root.join("src"), &all));
+        wanted
+            .into_iter()
+            .map(|n| {
+                let cp = all[&n];
+                (n, cp)
+            })
+            .collect()
+    };
+
+    let font_url = match (&source, names.is_empty()) {
+        (FontSource::Google { css_url }, false) => {
+            let list: Vec<&str> = icons.keys().map(String::as_str).collect();
+            source::google_css_ttf_url(css_url, &style.family(), &format!("&icon_names={}", list.join(",")))?
+        }
+        (FontSource::Mirror { .. }, false) => {
+            eprintln!("Warning: A font mirror can't subset icon fonts; using the full font.");
+            format!("{font_base}.ttf")
+        }
+        _ => format!("{font_base}.ttf"),
+    };
+
+    println!("Downloading {}...", style.family());
+    let data = source::fetch(&font_url, MAX_FONT_BYTES)
+        .map_err(|e| format!("Failed to download icon font: {e}"))?;
+    let fonts_dir = project_root.join("assets/fonts");
+    fs::create_dir_all(&fonts_dir)
+        .map_err(|e| format!("Failed to create {}: {e}", fonts_dir.display()))?;
+    let font_file = format!("{stem}.ttf");
+    fs::write(fonts_dir.join(&font_file), &data)
+        .map_err(|e| format!("Failed to write {font_file}: {e}"))?;
+    println!("  Saved to {}", fonts_dir.join(&font_file).display());
+    lock::record_font(project_root, &style.family(), &font_file, &font_url)?;
+
+    match record_license(&source, style, &font_file, &fonts_dir) {
+        Ok(record) => lock::record_license(project_root, &record)?,
+        Err(e) => eprintln!("Warning: {e}"),
+    }
+
+    let module_path = project_root.join("src/icons.rs");
+    if let Ok(existing) = fs::read_to_string(&module_path) {
+        if !existing.starts_with(codegen::GENERATED_MARKER) {
+            return Err(format!(
+                "{} exists and wasn't generated by plyx; move it out of the way first.",
+                module_path.display()
+            ));
+        }
+    }
+    fs::write(&module_path, render_module(style, &font_file, &icons))
+        .map_err(|e| format!("Failed to write {}: {e}", module_path.display()))?;
+    println!("  Wrote src/icons.rs ({} icons)", icons.len());
+
+    codegen::regenerate(project_root)?;
+    let main_rs = fs::read_to_string(project_root.join("src/main.rs")).unwrap_or_default();
+    if !main_rs.contains("mod icons;") {
+        println!("  Tip: add `mod icons;` to src/main.rs to use the icon constants.");
+    }
+    Ok(())
+}
+
+/// Material Symbols are Apache-2.0, with the license at the repo root.
+fn record_license(
+    source: &FontSource,
+    style: Style,
+    font_file: &str,
+    fonts_dir: &Path,
+) -> Result<LicenseRecord, String> {
+    let url = match source {
+        FontSource::Mirror { root } => format!("{root}/icons/LICENSE"),
+        _ => format!("{MATERIAL_REPO_URL}/LICENSE"),
+    };
+    let text = source::fetch(&url, MAX_FONT_BYTES)
+        .map_err(|e| format!("No license found for {}: {e}", style.family()))?;
+    let dir = license::licenses_dir(fonts_dir);
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+    let license_file = font_file.replace(".ttf", ".txt");
+    fs::write(dir.join(&license_file), &text)
+        .map_err(|e| format!("Failed to write license for {}: {e}", style.family()))?;
+
+    let record = LicenseRecord {
+        family: style.family(),
+        font_file: font_file.to_string(),
+        license: license::detect_spdx(&String::from_utf8_lossy(&text)).to_string(),
+        license_file,
+        source: url,
+    };
+    license::record_entry(fonts_dir, &record)?;
+    Ok(record)
+}
+
+/// Percent-encode the brackets and commas in the variable font file name.
+fn url_escape(s: &str) -> String {
+    s.replace('[', "%5B").replace(']', "%5D").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_icon_module() {
+        assert_eq!(Style::parse("material-symbols"), Some(Style::Outlined));
+        assert_eq!(Style::parse("material-symbols-rounded"), Some(Style::Rounded));
+        assert_eq!(Style::parse("font-awesome"), None);
+        assert_eq!(Style::Sharp.family(), "Material Symbols Sharp");
+
+        let icons = parse_codepoints("10k e951\nsettings e8b8\n\nbroken zz\n");
+        assert_eq!(icons.len(), 2);
+        assert_eq!(const_name("10k"), "ICON_10K");
+
+        let src = render_module(Style::Outlined, "material_symbols_outlined.ttf", &icons);
+        assert!(src.starts_with(codegen::GENERATED_MARKER));
+        assert!(src.contains("pub const SETTINGS: &str = \"\\u{e8b8}\";"));
+        assert!(src.contains("pub const ICON_10K: &str = \"\\u{e951}\";"));
+    }
+}
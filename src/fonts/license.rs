@@ -0,0 +1,213 @@
+//! Font licenses — fetched next to each font so builds can ship them.
+//!
+//! Every downloaded font gets its license text saved to
+//! `assets/fonts/licenses/<family_name>.txt` and an entry in
+//! `assets/fonts/licenses/manifest.json`. Because they live under `assets/`,
+//! they end up in every APK, app bundle and web build. `plyx licenses` turns
+//! the manifest into a single `THIRD_PARTY_NOTICES` file.
+//!
+//! Google Fonts and Fontsource licenses come from the `google/fonts` GitHub
+//! repo (`ofl/`, `apache/` or `ufl/`); a mirror serves them from
+//! `<root>/licenses/<family_name>.txt`.
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::source::{self, FontSource};
+
+const GOOGLE_FONTS_REPO_URL: &str = "https://raw.githubusercontent.com/google/fonts/main";
+
+/// `(directory in google/fonts, license file name)`, in lookup order.
+const GOOGLE_LICENSE_DIRS: &[(&str, &str)] = &[
+    ("ofl", "OFL.txt"),
+    ("apache", "LICENSE.txt"),
+    ("ufl", "UFL.txt"),
+];
+
+/// Largest license text plyx will download.
+const MAX_LICENSE_BYTES: u64 = 1024 * 1024;
+
+/// One font's license, as recorded in `manifest.json`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LicenseRecord {
+    pub family: String,
+    /// Font file name in `assets/fonts/`.
+    pub font_file: String,
+    /// SPDX identifier, e.g. `OFL-1.1`.
+    pub license: String,
+    /// License text file name in `assets/fonts/licenses/`.
+    pub license_file: String,
+    /// Where the license text was fetched from.
+    pub source: String,
+}
+
+/// `assets/fonts/licenses/` for a given fonts directory.
+pub fn licenses_dir(fonts_dir: &Path) -> PathBuf {
+    fonts_dir.join("licenses")
+}
+
+fn manifest_path(fonts_dir: &Path) -> PathBuf {
+    licenses_dir(fonts_dir).join("manifest.json")
+}
+
+/// Read the manifest; a missing file is an empty manifest.
+pub fn read_manifest(fonts_dir: &Path) -> Result<Vec<LicenseRecord>, String> {
+    let path = manifest_path(fonts_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let data = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    serde_json::from_str(&data).map_err(|e| format!("Failed to parse {}: {e}", path.display()))
+}
+
+fn write_manifest(fonts_dir: &Path, records: &[LicenseRecord]) -> Result<(), String> {
+    let path = manifest_path(fonts_dir);
+    let json = serde_json::to_string_pretty(records)
+        .map_err(|e| format!("Failed to serialize license manifest: {e}"))?;
+    std::fs::write(&path, json + "\n")
+        .map_err(|e| format!("Failed to write {}: {e}", path.display()))
+}
+
+/// Fetch the license for `family` and record it for `font_file`.
+pub fn fetch_and_record(
+    source: &FontSource,
+    family: &str,
+    font_file: &str,
+    fonts_dir: &Path,
+) -> Result<LicenseRecord, String> {
+    let (url, text) = fetch_license(source, family)?;
+
+    let dir = licenses_dir(fonts_dir);
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {e}", dir.display()))?;
+
+    let stem = Path::new(font_file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(font_file);
+    let license_file = format!("{stem}.txt");
+    std::fs::write(dir.join(&license_file), &text)
+        .map_err(|e| format!("Failed to write license for {family}: {e}"))?;
+
+    let record = LicenseRecord {
+        family: family.to_string(),
+        font_file: font_file.to_string(),
+        license: detect_spdx(&text).to_string(),
+        license_file,
+        source: url,
+    };
+
+    record_entry(fonts_dir, &record)?;
+    Ok(record)
+}
+
+/// Add or replace the manifest entry for `record.font_file`.
+pub fn record_entry(fonts_dir: &Path, record: &LicenseRecord) -> Result<(), String> {
+    let mut records = read_manifest(fonts_dir)?;
+    records.retain(|r| r.font_file != record.font_file);
+    records.push(record.clone());
+    records.sort_by(|a, b| a.font_file.cmp(&b.font_file));
+    write_manifest(fonts_dir, &records)
+}
+
+/// Drop the manifest entry and license text for `font_file`, if any.
+pub fn forget(font_file: &str, fonts_dir: &Path) -> Result<(), String> {
+    let mut records = read_manifest(fonts_dir)?;
+    let Some(pos) = records.iter().position(|r| r.font_file == font_file) else {
+        return Ok(());
+    };
+    let record = records.remove(pos);
+    let _ = std::fs::remove_file(licenses_dir(fonts_dir).join(&record.license_file));
+    write_manifest(fonts_dir, &records)
+}
+
+/// Try each candidate location and return `(url, text)` for the first hit.
+fn fetch_license(source: &FontSource, family: &str) -> Result<(String, String), String> {
+    let candidates = license_urls(source, family);
+    for url in &candidates {
+        if let Ok(bytes) = source::fetch(url, MAX_LICENSE_BYTES) {
+            let text = String::from_utf8_lossy(&bytes).into_owned();
+            return Ok((url.clone(), text));
+        }
+    }
+    Err(format!("No license found for {family} (tried {}).", candidates.join(", ")))
+}
+
+fn license_urls(source: &FontSource, family: &str) -> Vec<String> {
+    match source {
+        FontSource::Mirror { root } => {
+            let stem = super::font_filename(family).trim_end_matches(".ttf").to_string();
+            vec![format!("{root}/licenses/{stem}.txt")]
+        }
+        FontSource::Google { .. } | FontSource::Fontsource { .. } => {
+            let dir = google_repo_dir(family);
+            GOOGLE_LICENSE_DIRS
+                .iter()
+                .map(|(kind, file)| format!("{GOOGLE_FONTS_REPO_URL}/{kind}/{dir}/{file}"))
+                .collect()
+        }
+    }
+}
+
+/// Directory name in the google/fonts repo: "IBM Plex Sans" → "ibmplexsans".
+fn google_repo_dir(family: &str) -> String {
+    family
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Best-effort SPDX identifier from a license text.
+pub fn detect_spdx(text: &str) -> &'static str {
+    let upper = text.to_uppercase();
+    if upper.contains("SIL OPEN FONT LICENSE") {
+        "OFL-1.1"
+    } else if upper.contains("APACHE LICENSE") {
+        "Apache-2.0"
+    } else if upper.contains("UBUNTU FONT LICENCE") {
+        "UFL-1.0"
+    } else {
+        "LicenseRef-Unknown"
+    }
+}
+
+/// Render a `THIRD_PARTY_NOTICES` document from the manifest.
+pub fn render_notices(fonts_dir: &Path, records: &[LicenseRecord]) -> Result<String, String> {
+    let rule = "=".repeat(72);
+    let mut out = String::from(
+        "THIRD-PARTY NOTICES\n\n\
+         This application bundles the following third-party fonts.\n",
+    );
+
+    for record in records {
+        let text_path = licenses_dir(fonts_dir).join(&record.license_file);
+        let text = std::fs::read_to_string(&text_path)
+            .map_err(|e| format!("Failed to read {}: {e}", text_path.display()))?;
+
+        out.push_str(&format!(
+            "\n{rule}\n{} ({})\nLicense: {}\nSource: {}\n{rule}\n\n",
+            record.family, record.font_file, record.license, record.source
+        ));
+        out.push_str(text.trim_end());
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_license_urls() {
+        let urls = license_urls(&FontSource::default(), "IBM Plex Sans");
+        assert_eq!(
+            urls[0],
+            "https://raw.githubusercontent.com/google/fonts/main/ofl/ibmplexsans/OFL.txt"
+        );
+        assert_eq!(detect_spdx("This Font Software is licensed under the SIL Open Font License"), "OFL-1.1");
+    }
+}
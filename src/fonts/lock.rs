@@ -0,0 +1,367 @@
+//! `plyx.lock` — what plyx downloaded, from where, and its SHA-256.
+//!
+//! Every font and license text plyx fetches gets an entry, with paths
+//! relative to the project root:
+//!
+//! ```toml
+//! version = 1
+//!
+//! [[font]]
+//! family = "Lexend"
+//! weight = 400
+//! file = "assets/fonts/lexend.ttf"
+//! url = "https://fonts.gstatic.com/s/lexend/v19/….ttf"
+//! upstream-version = "1.005"
+//! sha256 = "…"
+//!
+//! [[license]]
+//! family = "Lexend"
+//! font-file = "lexend.ttf"
+//! license = "OFL-1.1"
+//! file = "assets/fonts/licenses/lexend.txt"
+//! url = "https://raw.githubusercontent.com/google/fonts/main/ofl/lexend/OFL.txt"
+//! sha256 = "…"
+//! ```
+//!
+//! With the lock committed, `assets/fonts/` can be gitignored and restored
+//! with `plyx fonts sync`.
+
+use std::path::{Component, Path};
+
+use sha2::{Digest, Sha256};
+use toml_edit::{value, ArrayOfTables, DocumentMut, Item, Table};
+
+use super::license::{self, LicenseRecord};
+use super::metadata::FontMeta;
+
+pub const LOCK_FILE: &str = "plyx.lock";
+
+const LOCK_VERSION: i64 = 1;
+
+const HEADER: &str = "# Written by plyx. Records every downloaded font and license so\n\
+                      # `plyx fonts sync` can restore them. Commit this file.\n";
+
+/// A downloaded font file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedFont {
+    pub family: String,
+    pub weight: u16,
+    /// Path relative to the project root.
+    pub file: String,
+    pub url: String,
+    /// Version string from the font's `name` table.
+    pub upstream_version: Option<String>,
+    pub sha256: String,
+}
+
+/// A downloaded license text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedLicense {
+    pub family: String,
+    /// Font file name in `assets/fonts/` this license covers.
+    pub font_file: String,
+    /// SPDX identifier.
+    pub license: String,
+    /// Path relative to the project root.
+    pub file: String,
+    pub url: String,
+    pub sha256: String,
+}
+
+impl LockedLicense {
+    /// The `manifest.json` entry this license corresponds to.
+    pub fn to_record(&self) -> LicenseRecord {
+        LicenseRecord {
+            family: self.family.clone(),
+            font_file: self.font_file.clone(),
+            license: self.license.clone(),
+            license_file: file_name(&self.file),
+            source: self.url.clone(),
+        }
+    }
+}
+
+/// Contents of `plyx.lock`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Lock {
+    pub fonts: Vec<LockedFont>,
+    pub licenses: Vec<LockedLicense>,
+}
+
+impl Lock {
+    /// Read `<project_root>/plyx.lock`; a missing file is an empty lock.
+    pub fn read(project_root: &Path) -> Result<Self, String> {
+        let path = project_root.join(LOCK_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {LOCK_FILE}: {e}"))?;
+        Self::parse(&text).map_err(|e| format!("Failed to parse {LOCK_FILE}: {e}"))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let doc: DocumentMut = text.parse().map_err(|e| format!("{e}"))?;
+        let version = doc.get("version").and_then(Item::as_integer).unwrap_or(LOCK_VERSION);
+        if version > LOCK_VERSION {
+            return Err(format!(
+                "lock version {version} is newer than this plyx understands; update plyx"
+            ));
+        }
+
+        let tables = |key: &str| -> Vec<Table> {
+            doc.get(key)
+                .and_then(Item::as_array_of_tables)
+                .map(|a| a.iter().cloned().collect())
+                .unwrap_or_default()
+        };
+
+        let fonts = tables("font")
+            .iter()
+            .map(|t| {
+                Ok(LockedFont {
+                    family: req(t, "family")?,
+                    weight: t
+                        .get("weight")
+                        .and_then(Item::as_integer)
+                        .and_then(|w| u16::try_from(w).ok())
+                        .unwrap_or(400),
+                    file: asset_path(t, "file")?,
+                    url: req(t, "url")?,
+                    upstream_version: opt(t, "upstream-version"),
+                    sha256: req(t, "sha256")?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        let licenses = tables("license")
+            .iter()
+            .map(|t| {
+                Ok(LockedLicense {
+                    family: req(t, "family")?,
+                    font_file: plain_name(t, "font-file")?,
+                    license: opt(t, "license").unwrap_or_else(|| "LicenseRef-Unknown".into()),
+                    file: asset_path(t, "file")?,
+                    url: req(t, "url")?,
+                    sha256: req(t, "sha256")?,
+                })
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Lock { fonts, licenses })
+    }
+
+    pub fn render(&self) -> String {
+        let mut doc = DocumentMut::new();
+        doc["version"] = value(LOCK_VERSION);
+
+        let mut fonts = ArrayOfTables::new();
+        for f in &self.fonts {
+            let mut t = Table::new();
+            t["family"] = value(&f.family);
+            t["weight"] = value(i64::from(f.weight));
+            t["file"] = value(&f.file);
+            t["url"] = value(&f.url);
+            if let Some(v) = &f.upstream_version {
+                t["upstream-version"] = value(v);
+            }
+            t["sha256"] = value(&f.sha256);
+            fonts.push(t);
+        }
+        if !fonts.is_empty() {
+            doc["font"] = Item::ArrayOfTables(fonts);
+        }
+
+        let mut licenses = ArrayOfTables::new();
+        for l in &self.licenses {
+            let mut t = Table::new();
+            t["family"] = value(&l.family);
+            t["font-file"] = value(&l.font_file);
+            t["license"] = value(&l.license);
+            t["file"] = value(&l.file);
+            t["url"] = value(&l.url);
+            t["sha256"] = value(&l.sha256);
+            licenses.push(t);
+        }
+        if !licenses.is_empty() {
+            doc["license"] = Item::ArrayOfTables(licenses);
+        }
+
+        format!("{HEADER}{doc}")
+    }
+
+    pub fn write(&self, project_root: &Path) -> Result<(), String> {
+        std::fs::write(project_root.join(LOCK_FILE), self.render())
+            .map_err(|e| format!("Failed to write {LOCK_FILE}: {e}"))
+    }
+
+    /// Read, modify and write back `<project_root>/plyx.lock`.
+    fn update(project_root: &Path, f: impl FnOnce(&mut Lock)) -> Result<(), String> {
+        let mut lock = Self::read(project_root)?;
+        f(&mut lock);
+        lock.fonts.sort_by(|a, b| a.file.cmp(&b.file));
+        lock.licenses.sort_by(|a, b| a.file.cmp(&b.file));
+        lock.write(project_root)
+    }
+}
+
+/// Record a font [`download`](super::download)ed into the project's
+/// `assets/fonts/`, and its license.
+pub fn record_download(project_root: &Path, font: &super::Downloaded) -> Result<(), String> {
+    let font_file = font.path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    record_font(project_root, &font.family, font_file, &font.url)?;
+    match &font.license {
+        Some(record) => record_license(project_root, record),
+        None => Ok(()),
+    }
+}
+
+/// Record a freshly downloaded font file in `<project_root>/assets/fonts/`.
+pub fn record_font(
+    project_root: &Path,
+    family: &str,
+    font_file: &str,
+    url: &str,
+) -> Result<(), String> {
+    let path = project_root.join(relative_file(font_file));
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let meta = FontMeta::parse(&data).ok();
+    let entry = LockedFont {
+        family: family.to_string(),
+        weight: meta.as_ref().map_or(400, |m| m.weight),
+        file: relative_file(font_file),
+        url: url.to_string(),
+        upstream_version: meta.and_then(|m| m.version),
+        sha256: sha256_hex(&data),
+    };
+    Lock::update(project_root, |lock| {
+        lock.fonts.retain(|f| f.file != entry.file);
+        lock.fonts.push(entry);
+    })
+}
+
+/// Record a freshly fetched license text in
+/// `<project_root>/assets/fonts/licenses/`.
+pub fn record_license(project_root: &Path, record: &LicenseRecord) -> Result<(), String> {
+    let path = license::licenses_dir(&project_root.join("assets/fonts")).join(&record.license_file);
+    let data = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let entry = LockedLicense {
+        family: record.family.clone(),
+        font_file: record.font_file.clone(),
+        license: record.license.clone(),
+        file: format!("assets/fonts/licenses/{}", record.license_file),
+        url: record.source.clone(),
+        sha256: sha256_hex(&data),
+    };
+    Lock::update(project_root, |lock| {
+        lock.licenses.retain(|l| l.font_file != entry.font_file);
+        lock.licenses.push(entry);
+    })
+}
+
+/// Drop the entries for `font_file` (and its license).
+pub fn forget(project_root: &Path, font_file: &str) -> Result<(), String> {
+    if !project_root.join(LOCK_FILE).exists() {
+        return Ok(());
+    }
+    let file = relative_file(font_file);
+    Lock::update(project_root, |lock| {
+        lock.fonts.retain(|f| f.file != file);
+        lock.licenses.retain(|l| l.font_file != font_file);
+    })
+}
+
+/// Lowercase hex SHA-256 of `data`.
+pub fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data).iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn relative_file(font_file: &str) -> String {
+    format!("assets/fonts/{font_file}")
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(path)
+        .to_string()
+}
+
+fn req(t: &Table, key: &str) -> Result<String, String> {
+    opt(t, key).ok_or_else(|| format!("entry is missing `{key}`"))
+}
+
+/// A `file` path, which `plyx fonts sync` writes to: it has to stay inside
+/// `assets/`, so a crafted lock can't write anywhere else.
+fn asset_path(t: &Table, key: &str) -> Result<String, String> {
+    let path = req(t, key)?;
+    let mut components = Path::new(&path).components();
+    let inside = components.next() == Some(Component::Normal("assets".as_ref()))
+        && components.clone().next().is_some()
+        && components.all(|c| matches!(c, Component::Normal(_)))
+        && !path.contains('\\');
+    if !inside {
+        return Err(format!("{key} \"{path}\" must be a relative path inside assets/"));
+    }
+    Ok(path)
+}
+
+/// A file name without any directory part.
+fn plain_name(t: &Table, key: &str) -> Result<String, String> {
+    let name = req(t, key)?;
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(format!("{key} \"{name}\" must be a plain file name"));
+    }
+    Ok(name)
+}
+
+fn opt(t: &Table, key: &str) -> Option<String> {
+    t.get(key).and_then(Item::as_str).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_roundtrip() {
+        let lock = Lock {
+            fonts: vec![LockedFont {
+                family: "Lexend".into(),
+                weight: 400,
+                file: "assets/fonts/lexend.ttf".into(),
+                url: "https://example.com/lexend.ttf".into(),
+                upstream_version: Some("1.005".into()),
+                sha256: sha256_hex(b"abc"),
+            }],
+            licenses: vec![LockedLicense {
+                family: "Lexend".into(),
+                font_file: "lexend.ttf".into(),
+                license: "OFL-1.1".into(),
+                file: "assets/fonts/licenses/lexend.txt".into(),
+                url: "https://example.com/OFL.txt".into(),
+                sha256: sha256_hex(b""),
+            }],
+        };
+        assert_eq!(
+            lock.fonts[0].sha256,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+
+        let text = lock.render();
+        assert!(text.contains("[[font]]") && text.contains("upstream-version = \"1.005\""));
+        assert_eq!(Lock::parse(&text).unwrap(), lock);
+        assert_eq!(lock.licenses[0].to_record().license_file, "lexend.txt");
+
+        assert!(Lock::parse("version = 99").is_err());
+
+        let entry = |file: &str| {
+            format!("[[font]]\nfamily = \"X\"\nfile = \"{file}\"\nurl = \"u\"\nsha256 = \"s\"\n")
+        };
+        for bad in ["../../.bashrc", "/etc/passwd", "assets/../src/main.rs", "src/x.ttf", "assets"] {
+            assert!(Lock::parse(&entry(bad)).is_err(), "{bad}");
+        }
+        assert!(Lock::parse(&entry("assets/fonts/./x.ttf")).is_ok());
+    }
+}
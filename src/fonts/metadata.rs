@@ -0,0 +1,188 @@
+//! What a font file says about itself, read from its `name` and `OS/2`
+//! tables.
+//!
+//! plyx names downloaded files after the family ("IBM Plex Sans" →
+//! `ibm_plex_sans.ttf`), but that can't be reversed reliably and says nothing
+//! about files added by hand. Reading the font itself can.
+
+use std::path::Path;
+
+use ttf_parser::name_id;
+
+/// Identity of one installed font file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontMeta {
+    /// Typographic family, e.g. "IBM Plex Sans".
+    pub family: String,
+    /// Style within the family, e.g. "SemiBold Italic".
+    pub subfamily: String,
+    /// Version string without the "Version " prefix, e.g. "3.001".
+    pub version: Option<String>,
+    /// `OS/2` weight class (100–900).
+    pub weight: u16,
+}
+
+impl FontMeta {
+    /// Parse font data (TTF/OTF, first face of a collection).
+    pub fn parse(data: &[u8]) -> Result<Self, String> {
+        let face = ttf_parser::Face::parse(data, 0).map_err(|e| format!("not a font ({e})"))?;
+
+        // Prefer the typographic names (16/17), which keep all weights of a
+        // family together; fall back to the legacy four-style names (1/2).
+        let family = name(&face, name_id::TYPOGRAPHIC_FAMILY)
+            .or_else(|| name(&face, name_id::FAMILY))
+            .ok_or("font has no family name")?;
+        let subfamily = name(&face, name_id::TYPOGRAPHIC_SUBFAMILY)
+            .or_else(|| name(&face, name_id::SUBFAMILY))
+            .unwrap_or_else(|| "Regular".to_string());
+        let version = name(&face, name_id::VERSION).map(|v| {
+            v.strip_prefix("Version ")
+                .unwrap_or(&v)
+                .split(';')
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string()
+        });
+
+        Ok(FontMeta {
+            family,
+            subfamily,
+            version,
+            weight: face.weight().to_number(),
+        })
+    }
+
+    /// Read and parse a font file.
+    pub fn read(path: &Path) -> Result<Self, String> {
+        let data =
+            std::fs::read(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        Self::parse(&data).map_err(|e| format!("{}: {e}", path.display()))
+    }
+}
+
+/// First Unicode (or Mac Roman) entry for `id`, trimmed.
+fn name(face: &ttf_parser::Face, id: u16) -> Option<String> {
+    face.names()
+        .into_iter()
+        .filter(|n| n.name_id == id)
+        .find_map(|n| n.to_string())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// One file in `assets/fonts/` and, if it parsed, its metadata.
+#[derive(Debug, Clone)]
+pub struct InstalledFont {
+    pub file: String,
+    pub meta: Option<FontMeta>,
+}
+
+impl InstalledFont {
+    /// Family from the font's `name` table, or guessed from the file name
+    /// when the file can't be parsed.
+    pub fn family(&self) -> String {
+        match &self.meta {
+            Some(meta) => meta.family.clone(),
+            None => super::family_from_filename(&self.file),
+        }
+    }
+}
+
+/// Every font file in `fonts_dir`, with metadata where readable.
+pub fn installed(fonts_dir: &Path) -> Vec<InstalledFont> {
+    super::installed_font_files(fonts_dir)
+        .into_iter()
+        .map(|file| InstalledFont {
+            meta: FontMeta::read(&fonts_dir.join(&file)).ok(),
+            file,
+        })
+        .collect()
+}
+
+/// Whether a font of `family` is already in `fonts_dir`.
+pub fn is_installed(fonts_dir: &Path, family: &str) -> bool {
+    installed(fonts_dir)
+        .iter()
+        .any(|f| f.family().eq_ignore_ascii_case(family))
+}
+
+/// A minimal but valid TrueType font with the given names, for tests.
+#[cfg(test)]
+pub(crate) fn test_font(family: &str, subfamily: &str, weight: u16) -> Vec<u8> {
+    fn be16(out: &mut Vec<u8>, v: u16) {
+        out.extend_from_slice(&v.to_be_bytes());
+    }
+
+    let mut head = vec![0u8; 54];
+    head[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    head[12..16].copy_from_slice(&0x5F0F_3CF5u32.to_be_bytes());
+    head[18..20].copy_from_slice(&1000u16.to_be_bytes());
+
+    let mut hhea = vec![0u8; 36];
+    hhea[..4].copy_from_slice(&0x0001_0000u32.to_be_bytes());
+    hhea[34..36].copy_from_slice(&1u16.to_be_bytes());
+
+    let mut maxp = 0x0000_5000u32.to_be_bytes().to_vec();
+    be16(&mut maxp, 1);
+
+    let mut os2 = vec![0u8; 78];
+    os2[4..6].copy_from_slice(&weight.to_be_bytes());
+    os2[6..8].copy_from_slice(&5u16.to_be_bytes());
+
+    let strings = [(1, family), (2, subfamily), (5, "Version 1.002; test")];
+    let mut name = Vec::new();
+    be16(&mut name, 0);
+    be16(&mut name, strings.len() as u16);
+    be16(&mut name, 6 + 12 * strings.len() as u16);
+    let mut storage = Vec::new();
+    for (id, text) in strings {
+        let encoded: Vec<u8> = text.encode_utf16().flat_map(u16::to_be_bytes).collect();
+        for v in [3, 1, 0x0409, id, encoded.len() as u16, storage.len() as u16] {
+            be16(&mut name, v);
+        }
+        storage.extend(encoded);
+    }
+    name.extend(storage);
+
+    let tables: [(&[u8; 4], Vec<u8>); 5] = [
+        (b"OS/2", os2),
+        (b"head", head),
+        (b"hhea", hhea),
+        (b"maxp", maxp),
+        (b"name", name),
+    ];
+    let mut out = 0x0001_0000u32.to_be_bytes().to_vec();
+    for v in [tables.len() as u16, 0, 0, 0] {
+        be16(&mut out, v);
+    }
+    let mut offset = 12 + 16 * tables.len();
+    for (tag, data) in &tables {
+        out.extend_from_slice(*tag);
+        out.extend_from_slice(&0u32.to_be_bytes());
+        out.extend_from_slice(&(offset as u32).to_be_bytes());
+        out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        offset += data.len().next_multiple_of(4);
+    }
+    for (_, data) in &tables {
+        out.extend_from_slice(data);
+        out.resize(out.len().next_multiple_of(4), 0);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name_table() {
+        let meta = FontMeta::parse(&test_font("IBM Plex Sans", "SemiBold", 600)).unwrap();
+        assert_eq!(meta.family, "IBM Plex Sans");
+        assert_eq!(meta.subfamily, "SemiBold");
+        assert_eq!(meta.version.as_deref(), Some("1.002"));
+        assert_eq!(meta.weight, 600);
+
+        assert!(FontMeta::parse(b"not really a font").is_err());
+    }
+}
@@ -0,0 +1,564 @@
+//! Font catalog integration — search the popularity-sorted font list and download.
+//!
+//! The font list is a JSON array of families with optional metadata
+//! (category, subsets, weights; see [`catalog`]), pre-sorted by
+//! popularity (index 0 = most popular). It is generated by `buildfontlist.sh`
+//! and committed to the plyx repo as `fontlist-v2.json`; `fontlist.json`
+//! holds the same list as bare names for older plyx releases. At runtime we
+//! fetch it from the configured [`FontSource`] (GitHub by default), cache it
+//! locally, and use it for search / selection. A copy is also embedded in the binary so the catalog
+//! is available on a first offline run.
+//!
+//! Font files are downloaded from the configured source; see [`source`].
+
+pub mod catalog;
+pub mod codegen;
+pub mod icons;
+pub mod license;
+pub mod lock;
+pub mod metadata;
+pub mod preview;
+pub mod search;
+pub mod source;
+
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use sha2::{Digest, Sha256};
+
+use crate::{net, tui};
+pub use catalog::{Category, FontInfo, FontQuery};
+pub use source::FontSource;
+
+/// Catalog snapshot from build time, used when neither GitHub nor the cache
+/// is available.
+const EMBEDDED_FONT_LIST: &str = include_str!("../../fontlist-v2.json");
+
+pub(crate) fn cache_dir() -> PathBuf {
+    let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
+    PathBuf::from(home).join(".cache").join("plyx")
+}
+
+/// The cached catalog of `source`. Each catalog location gets its own file,
+/// so switching sources never serves another source's list.
+fn cache_path(source: &FontSource) -> PathBuf {
+    let hash = Sha256::digest(source.catalog_location().as_bytes());
+    let key: String = hash[..6].iter().map(|b| format!("{b:02x}")).collect();
+    cache_dir().join(format!("fontlist-{key}.json"))
+}
+
+fn fetch_font_list(source: &FontSource, announce: bool) -> Result<Vec<FontInfo>, String> {
+    if announce {
+        println!("Fetching {} catalog...", source.name());
+    }
+
+    let body = source.fetch_catalog()?;
+    catalog::parse(&body).map_err(|e| format!("Failed to parse font list: {e}"))
+}
+
+/// Load the popularity-sorted font list.
+///
+/// Tries to fetch the latest list from the configured source (unless it is
+/// remote and we're offline). If that fails, falls back to the locally cached
+/// copy, and finally to the catalog embedded at build time.
+pub fn load_font_list() -> Result<Vec<FontInfo>, String> {
+    load_font_list_with(true, &mut |w| eprintln!("Warning: {w}"))
+}
+
+/// Start loading the font list on a background thread, so it can arrive
+/// while the user is busy with something else. Warnings are held back until
+/// [`PendingFontList::wait`] so they don't garble an interactive prompt.
+pub fn spawn_font_list() -> PendingFontList {
+    PendingFontList(std::thread::spawn(|| {
+        let mut warnings = Vec::new();
+        let result = load_font_list_with(false, &mut |w| warnings.push(w));
+        (result, warnings)
+    }))
+}
+
+/// The loaded list, and the warnings raised while loading it.
+type LoadedFontList = (Result<Vec<FontInfo>, String>, Vec<String>);
+
+/// A font list being loaded by [`spawn_font_list`].
+pub struct PendingFontList(JoinHandle<LoadedFontList>);
+
+impl PendingFontList {
+    /// Block until the list is loaded, then print any warnings.
+    pub fn wait(self) -> Result<Vec<FontInfo>, String> {
+        let (result, warnings) = self
+            .0
+            .join()
+            .map_err(|_| "Font list loader panicked".to_string())?;
+        for w in warnings {
+            eprintln!("Warning: {w}");
+        }
+        result
+    }
+}
+
+fn load_font_list_with(
+    announce: bool,
+    warn: &mut dyn FnMut(String),
+) -> Result<Vec<FontInfo>, String> {
+    let source = FontSource::configured()?;
+    if !(source.is_remote() && net::is_offline()) {
+        match fetch_font_list(&source, announce) {
+            Ok(fonts) => {
+                // Update cache on success
+                let dir = cache_dir();
+                std::fs::create_dir_all(&dir).ok();
+                if let Ok(json) = serde_json::to_string(&fonts) {
+                    std::fs::write(cache_path(&source), json).ok();
+                }
+                return Ok(fonts);
+            }
+            Err(e) => warn(e),
+        }
+    }
+
+    let path = cache_path(&source);
+    if path.exists() {
+        match read_cached_font_list(&path) {
+            Ok(fonts) => {
+                if !net::is_offline() {
+                    warn("Could not fetch latest font list, using cached version.".to_string());
+                }
+                return Ok(fonts);
+            }
+            Err(e) => warn(e),
+        }
+    }
+
+    if !net::is_offline() {
+        warn("Using the font list bundled with plyx.".to_string());
+    }
+    catalog::parse(EMBEDDED_FONT_LIST)
+        .map_err(|e| format!("Failed to parse embedded font list: {e}"))
+}
+
+fn read_cached_font_list(path: &Path) -> Result<Vec<FontInfo>, String> {
+    let data =
+        std::fs::read_to_string(path).map_err(|e| format!("Failed to read cache: {e}"))?;
+    catalog::parse(&data).map_err(|e| format!("Failed to parse cache: {e}"))
+}
+
+/// Search fonts by name (typo-tolerant, see [`search`]) and, when the
+/// catalog has metadata, filters such as `mono:` or `script:cyrillic` (see
+/// [`FontQuery`]). Results are ordered by relevance, then popularity.
+pub fn search<'a>(fonts: &'a [FontInfo], query: &str) -> Vec<&'a FontInfo> {
+    let query = FontQuery::for_catalog(query, fonts);
+    let matching: Vec<&FontInfo> = fonts
+        .iter()
+        .filter(|f| query.matches_filters(Some(f)))
+        .collect();
+    search::rank(&matching, &query.text, |f| &f.family)
+        .into_iter()
+        .map(|(i, _)| matching[i])
+        .collect()
+}
+
+/// Resolve a non-interactive query to one font, or to a short list of
+/// candidates when it's ambiguous.
+pub fn resolve<'a>(fonts: &'a [FontInfo], query: &str) -> search::Resolution<'a, FontInfo> {
+    if let Some(exact) = find_by_name(fonts, query.trim()) {
+        return search::Resolution::Found(exact);
+    }
+    let query = FontQuery::for_catalog(query, fonts);
+    let matching: Vec<&FontInfo> = fonts
+        .iter()
+        .filter(|f| query.matches_filters(Some(f)))
+        .collect();
+    let ranked = search::rank(&matching, &query.text, |f| &f.family);
+    match search::resolve(&ranked, &matching, 8) {
+        search::Resolution::Found(f) => search::Resolution::Found(*f),
+        search::Resolution::Ambiguous(c) => {
+            search::Resolution::Ambiguous(c.into_iter().copied().collect())
+        }
+        search::Resolution::NotFound => search::Resolution::NotFound,
+    }
+}
+
+/// Find a font by exact name (case-insensitive).
+pub fn find_by_name<'a>(fonts: &'a [FontInfo], name: &str) -> Option<&'a FontInfo> {
+    fonts.iter().find(|f| f.family.eq_ignore_ascii_case(name))
+}
+
+/// Suggested fonts shown at the top of the selection list during `plyx init`.
+/// These are displayed when the user hasn't typed a search query.
+pub const SUGGESTED_FONTS: &[&str] = &[
+    "Lexend", // This should have the text " (Default)" next to it
+    "Inter",
+    "Roboto",
+    "Open Sans",
+    "Montserrat",
+    "Nunito",
+];
+
+/// Default font name.
+pub const DEFAULT_FONT: &str = "Lexend";
+
+/// A font saved by [`download`], for recording in `plyx.lock` (see
+/// [`lock::record_download`]).
+pub struct Downloaded {
+    pub family: String,
+    pub path: PathBuf,
+    /// Where the font file came from.
+    pub url: String,
+    pub license: Option<license::LicenseRecord>,
+}
+
+/// Download a font by family name from the configured [`FontSource`].
+///
+/// Saves the regular (400) weight as `<family_name>.ttf` in `dest_dir`, and
+/// its license under `dest_dir/licenses/` (see [`license`]). A missing
+/// license is a warning, not an error. Ctrl-C stops the download without
+/// leaving a partial file behind. Nothing is written to `plyx.lock`.
+pub fn download(family: &str, dest_dir: &Path) -> Result<Downloaded, String> {
+    let source = FontSource::configured()?;
+    let cancel = net::CancelOnCtrlC::install();
+    let (path, url) = source.download(family, dest_dir)?;
+    drop(cancel);
+    let font = Downloaded {
+        family: family.to_string(),
+        path,
+        url,
+        license: fetch_license(&source, family, dest_dir),
+    };
+    if let Some(record) = &font.license {
+        println!("  License: {}", record.license);
+    }
+    Ok(font)
+}
+
+/// Fetch the license of a downloaded font, or warn that there is none.
+fn fetch_license(
+    source: &FontSource,
+    family: &str,
+    dest_dir: &Path,
+) -> Option<license::LicenseRecord> {
+    license::fetch_and_record(source, family, &font_filename(family), dest_dir)
+        .map_err(|e| eprintln!("Warning: {e}"))
+        .ok()
+}
+
+// ── Parallel downloads ──────────────────────────────────────────────────
+
+/// How many fonts [`download_all`] fetches at once.
+const PARALLEL_DOWNLOADS: usize = 4;
+
+/// State of one font in [`download_all`].
+enum Job {
+    Waiting,
+    Running { done: u64, total: Option<u64> },
+    Done { path: PathBuf, url: String },
+    Failed(String),
+}
+
+impl Job {
+    fn is_finished(&self) -> bool {
+        matches!(self, Job::Done { .. } | Job::Failed(_))
+    }
+
+    fn status(&self) -> String {
+        match self {
+            Job::Waiting => "waiting".to_string(),
+            Job::Running { done, total: Some(total) } if *total > 0 => format!(
+                "{} / {} ({}%)",
+                net::human_size(*done),
+                net::human_size(*total),
+                done * 100 / total
+            ),
+            Job::Running { done, .. } => net::human_size(*done),
+            Job::Done { .. } => "done".to_string(),
+            Job::Failed(e) => format!("failed: {e}"),
+        }
+    }
+}
+
+/// What [`download_all`] saved, and what it couldn't.
+pub struct Downloads {
+    pub done: Vec<Downloaded>,
+    /// `family: error` for each font that failed.
+    pub failures: Vec<String>,
+}
+
+impl Downloads {
+    /// An error listing the failures, if there were any.
+    pub fn check(&self) -> Result<(), String> {
+        if self.failures.is_empty() {
+            return Ok(());
+        }
+        Err(format!(
+            "Failed to download {} font(s):\n  {}",
+            self.failures.len(),
+            self.failures.join("\n  ")
+        ))
+    }
+}
+
+/// Download several fonts at once, like [`download`], showing one progress
+/// line per font.
+///
+/// A failed font doesn't stop the others; the ones that arrived are
+/// returned with the failures. Ctrl-C stops every download in flight,
+/// removes the fonts this call had saved and returns an error.
+pub fn download_all(families: &[&str], dest_dir: &Path) -> Result<Downloads, String> {
+    if let [family] = families {
+        return match download(family, dest_dir) {
+            Ok(font) => Ok(Downloads { done: vec![font], failures: Vec::new() }),
+            Err(e) if e == net::CANCELLED => Err(e),
+            Err(e) => Ok(Downloads { done: Vec::new(), failures: vec![format!("{family}: {e}")] }),
+        };
+    }
+    let source = FontSource::configured()?;
+    let existed: Vec<bool> = families
+        .iter()
+        .map(|f| dest_dir.join(font_filename(f)).exists())
+        .collect();
+    let jobs: Vec<Mutex<Job>> = families.iter().map(|_| Mutex::new(Job::Waiting)).collect();
+    let set = |i: usize, job: Job| *jobs[i].lock().unwrap_or_else(|e| e.into_inner()) = job;
+    let next = AtomicUsize::new(0);
+    let live = std::io::stdout().is_terminal();
+
+    println!("Downloading {} fonts...", families.len());
+    let cancel = net::CancelOnCtrlC::install();
+    std::thread::scope(|scope| {
+        for _ in 0..PARALLEL_DOWNLOADS.min(families.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(family) = families.get(i) else { break };
+                if net::is_cancelled() {
+                    set(i, Job::Failed(net::CANCELLED.to_string()));
+                    continue;
+                }
+                let result = source.download_to(family, dest_dir, &mut |done, total| {
+                    set(i, Job::Running { done, total })
+                });
+                set(i, match result {
+                    Ok((path, url)) => Job::Done { path, url },
+                    Err(e) => Job::Failed(e),
+                });
+            });
+        }
+
+        let mut drawn = 0;
+        loop {
+            let (lines, finished) = progress_lines(families, &jobs);
+            if live {
+                drawn = tui::redraw_lines(&lines, drawn).unwrap_or(drawn);
+            }
+            if finished {
+                if !live {
+                    lines.iter().for_each(|l| println!("{l}"));
+                }
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(100));
+        }
+    });
+    let cancelled = net::is_cancelled();
+    drop(cancel);
+
+    let jobs: Vec<Job> = jobs
+        .into_iter()
+        .map(|j| j.into_inner().unwrap_or_else(|e| e.into_inner()))
+        .collect();
+    if cancelled {
+        for (job, existed) in jobs.iter().zip(&existed) {
+            if let (Job::Done { path, .. }, false) = (job, existed) {
+                let _ = std::fs::remove_file(path);
+            }
+        }
+        return Err(net::CANCELLED.to_string());
+    }
+
+    let mut downloads = Downloads { done: Vec::new(), failures: Vec::new() };
+    for (family, job) in families.iter().zip(jobs) {
+        match job {
+            Job::Done { path, url } => downloads.done.push(Downloaded {
+                family: family.to_string(),
+                path,
+                url,
+                license: fetch_license(&source, family, dest_dir),
+            }),
+            Job::Failed(e) => downloads.failures.push(format!("{family}: {e}")),
+            Job::Waiting | Job::Running { .. } => {}
+        }
+    }
+    Ok(downloads)
+}
+
+/// One line per font, and whether every download has finished.
+fn progress_lines(families: &[&str], jobs: &[Mutex<Job>]) -> (Vec<String>, bool) {
+    let width = families.iter().map(|f| f.len()).max().unwrap_or(0);
+    let mut finished = true;
+    let lines = families
+        .iter()
+        .zip(jobs)
+        .map(|(family, job)| {
+            let job = job.lock().unwrap_or_else(|e| e.into_inner());
+            finished &= job.is_finished();
+            format!("  {family:<width$}  {}", job.status())
+        })
+        .collect();
+    (lines, finished)
+}
+
+/// Fail early if fonts can't be downloaded right now (offline with a
+/// remote source).
+pub fn require_downloadable() -> Result<(), String> {
+    if FontSource::configured()?.is_remote() {
+        net::require_online("Downloading fonts")?;
+    }
+    Ok(())
+}
+
+/// File name plyx uses for a family: "Open Sans" → "open_sans.ttf".
+pub fn font_filename(family: &str) -> String {
+    family.to_lowercase().replace(' ', "_") + ".ttf"
+}
+
+/// Font files (`.ttf` / `.otf`) in `fonts_dir`, sorted by name.
+pub fn installed_font_files(fonts_dir: &Path) -> Vec<String> {
+    let mut files: Vec<String> = std::fs::read_dir(fonts_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_file())
+                .filter_map(|e| e.file_name().to_str().map(|s| s.to_string()))
+                .filter(|name| {
+                    let lower = name.to_lowercase();
+                    lower.ends_with(".ttf") || lower.ends_with(".otf")
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+}
+
+/// Best-effort reverse of [`font_filename`]: "open_sans.ttf" → "Open Sans".
+pub fn family_from_filename(file: &str) -> String {
+    let stem = file.strip_suffix(".ttf").unwrap_or(file);
+    stem.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().to_string() + &chars.collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Weight guessed from style words in a file name ("Inter-SemiBold.ttf" →
+/// 600); 400 when there are none.
+pub fn weight_from_filename(file: &str) -> u16 {
+    const WEIGHTS: &[(&str, u16)] = &[
+        ("extralight", 200),
+        ("ultralight", 200),
+        ("semibold", 600),
+        ("demibold", 600),
+        ("extrabold", 800),
+        ("ultrabold", 800),
+        ("hairline", 100),
+        ("thin", 100),
+        ("light", 300),
+        ("medium", 500),
+        ("bold", 700),
+        ("black", 900),
+        ("heavy", 900),
+    ];
+    let stem = Path::new(file)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(file)
+        .to_lowercase();
+    stem.split(|c: char| !c.is_ascii_alphanumeric())
+        .find_map(|word| {
+            WEIGHTS
+                .iter()
+                .find(|(name, _)| word.starts_with(name))
+                .map(|(_, w)| *w)
+        })
+        .unwrap_or(400)
+}
+
+/// Extract the first .ttf URL from Google Fonts CSS.
+fn extract_ttf_url(css: &str) -> Option<&str> {
+    // Look for url(...ttf) patterns
+    for segment in css.split("url(") {
+        if let Some(end) = segment.find(')') {
+            let url = &segment[..end];
+            if url.ends_with(".ttf") {
+                return Some(url);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_ttf_url() {
+        let css = r#"
+@font-face {
+  font-family: 'Lexend';
+  font-style: normal;
+  font-weight: 400;
+  src: url(https://fonts.gstatic.com/s/lexend/v23/abc.ttf) format('truetype');
+}
+"#;
+        let url = extract_ttf_url(css);
+        assert_eq!(url, Some("https://fonts.gstatic.com/s/lexend/v23/abc.ttf"));
+    }
+
+    #[test]
+    fn test_extract_ttf_url_no_match() {
+        let css = "@font-face { src: url(https://example.com/font.woff2); }";
+        assert_eq!(extract_ttf_url(css), None);
+    }
+
+    #[test]
+    fn test_embedded_font_list() {
+        let fonts = catalog::parse(EMBEDDED_FONT_LIST).unwrap();
+        assert!(fonts.len() > 1000);
+        assert!(find_by_name(&fonts, DEFAULT_FONT).is_some());
+    }
+
+    #[test]
+    #[ignore = "fontlist-v2.json has no metadata until buildfontlist.sh is rerun"]
+    fn test_embedded_font_list_metadata() {
+        let fonts = catalog::parse(EMBEDDED_FONT_LIST).unwrap();
+        assert!(catalog::has_metadata(&fonts));
+        assert!(!search(&fonts, "serif:").is_empty());
+    }
+
+    #[test]
+    fn test_download_lexend() {
+        let tmp = std::env::temp_dir().join("plyx_test_download");
+        let _ = std::fs::remove_dir_all(&tmp);
+
+        let result = download("Lexend", &tmp);
+        assert!(result.is_ok(), "Download failed: {:?}", result.err());
+
+        let path = result.unwrap().path;
+        assert!(path.exists(), "Font file not created");
+        assert!(
+            std::fs::metadata(&path).unwrap().len() > 1000,
+            "Font file too small"
+        );
+        assert_eq!(path.file_name().unwrap(), "lexend.ttf");
+
+        let _ = std::fs::remove_dir_all(&tmp);
+    }
+}
+
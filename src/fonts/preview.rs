@@ -0,0 +1,295 @@
+//! Sample-text previews for the font picker.
+//!
+//! The regular weight of a family is fetched once into
+//! `~/.cache/plyx/previews/`, rasterised on the CPU straight from its
+//! outlines, and the resulting greyscale bitmap is cached next to it as a
+//! PGM, so scrolling back to a font is instant and works offline.
+//!
+//! Fetching and rasterising happen on a background thread ([`Previewer`]) so
+//! typing in the picker never waits on the network.
+
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use ab_glyph_rasterizer::{point, Point, Rasterizer};
+use sha2::{Digest, Sha256};
+
+use super::FontSource;
+use crate::{config, net};
+
+/// Sample shown for every font.
+pub const SAMPLE_TEXT: &str = "The quick brown fox";
+
+/// An 8-bit greyscale image (0 = background, 255 = ink).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Bitmap {
+    fn new(width: usize, height: usize) -> Self {
+        Bitmap {
+            width,
+            height,
+            pixels: vec![0; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x]
+        } else {
+            0
+        }
+    }
+
+    /// Binary PGM (`P5`), the simplest format that round-trips exactly.
+    fn to_pgm(&self) -> Vec<u8> {
+        let mut out = format!("P5\n{} {}\n255\n", self.width, self.height).into_bytes();
+        out.extend_from_slice(&self.pixels);
+        out
+    }
+
+    fn from_pgm(data: &[u8]) -> Option<Self> {
+        // Header is three whitespace-separated fields after the magic.
+        let mut fields = Vec::new();
+        let mut pos = 0;
+        while fields.len() < 4 {
+            while data.get(pos)?.is_ascii_whitespace() {
+                pos += 1;
+            }
+            let start = pos;
+            while !data.get(pos)?.is_ascii_whitespace() {
+                pos += 1;
+            }
+            fields.push(std::str::from_utf8(&data[start..pos]).ok()?);
+        }
+        if fields[0] != "P5" || fields[3] != "255" {
+            return None;
+        }
+        let (width, height) = (fields[1].parse().ok()?, fields[2].parse().ok()?);
+        let pixels = data.get(pos + 1..)?.to_vec();
+        (pixels.len() == width * height).then_some(Bitmap {
+            width,
+            height,
+            pixels,
+        })
+    }
+}
+
+/// Lines to preview: the sample, plus `PLYX_PREVIEW_TEXT` or `preview-text`
+/// from project config if set.
+pub fn preview_lines() -> Vec<String> {
+    let mut lines = vec![SAMPLE_TEXT.to_string()];
+    let custom = std::env::var("PLYX_PREVIEW_TEXT")
+        .ok()
+        .or_else(|| config::get_str("preview-text"))
+        .filter(|t| !t.trim().is_empty());
+    lines.extend(custom);
+    lines
+}
+
+/// Rasterise `lines` of text, each `px` pixels tall, one below the other.
+pub fn rasterize(font_data: &[u8], lines: &[String], px: f32) -> Result<Bitmap, String> {
+    let face = ttf_parser::Face::parse(font_data, 0).map_err(|e| format!("not a font ({e})"))?;
+    let scale = px / (f32::from(face.ascender()) - f32::from(face.descender())).max(1.0);
+    let ascender = f32::from(face.ascender());
+    let line_height = px.ceil() as usize;
+
+    let advance = |c: char| {
+        face.glyph_index(c)
+            .and_then(|g| face.glyph_hor_advance(g))
+            .map_or(px * 0.5, |a| f32::from(a) * scale)
+    };
+    let width = lines
+        .iter()
+        .map(|l| l.chars().map(advance).sum::<f32>())
+        .fold(0.0, f32::max)
+        .ceil() as usize
+        + 1;
+    let height = line_height * lines.len();
+
+    let mut raster = Rasterizer::new(width, height);
+    for (row, line) in lines.iter().enumerate() {
+        let mut pen_x = 0.0;
+        for c in line.chars() {
+            if let Some(glyph) = face.glyph_index(c) {
+                let mut outline = Outline {
+                    raster: &mut raster,
+                    scale,
+                    origin: point(pen_x, row as f32 * line_height as f32 + ascender * scale),
+                    start: point(0.0, 0.0),
+                    last: point(0.0, 0.0),
+                };
+                face.outline_glyph(glyph, &mut outline);
+            }
+            pen_x += advance(c);
+        }
+    }
+
+    let mut bitmap = Bitmap::new(width, height);
+    raster.for_each_pixel_2d(|x, y, alpha| {
+        bitmap.pixels[y as usize * width + x as usize] = (alpha.clamp(0.0, 1.0) * 255.0) as u8;
+    });
+    Ok(bitmap)
+}
+
+/// Feeds `ttf-parser` outlines (font units, y up) into the rasteriser
+/// (pixels, y down).
+struct Outline<'a> {
+    raster: &'a mut Rasterizer,
+    scale: f32,
+    origin: Point,
+    start: Point,
+    last: Point,
+}
+
+impl Outline<'_> {
+    fn map(&self, x: f32, y: f32) -> Point {
+        point(self.origin.x + x * self.scale, self.origin.y - y * self.scale)
+    }
+}
+
+impl ttf_parser::OutlineBuilder for Outline<'_> {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.start = self.map(x, y);
+        self.last = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let p = self.map(x, y);
+        self.raster.draw_line(self.last, p);
+        self.last = p;
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let (c, p) = (self.map(x1, y1), self.map(x, y));
+        self.raster.draw_quad(self.last, c, p);
+        self.last = p;
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let (c1, c2, p) = (self.map(x1, y1), self.map(x2, y2), self.map(x, y));
+        self.raster.draw_cubic(self.last, c1, c2, p);
+        self.last = p;
+    }
+
+    fn close(&mut self) {
+        if self.last != self.start {
+            self.raster.draw_line(self.last, self.start);
+        }
+        self.last = self.start;
+    }
+}
+
+fn previews_dir() -> PathBuf {
+    super::cache_dir().join("previews")
+}
+
+/// Why [`load`] has no bitmap for a font.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreviewError {
+    /// The font isn't cached and the network is switched off.
+    Offline,
+    Failed(String),
+}
+
+impl From<String> for PreviewError {
+    fn from(e: String) -> Self {
+        PreviewError::Failed(e)
+    }
+}
+
+/// Cached bitmap, or fetch (if needed) and rasterise the font.
+pub fn load(family: &str, lines: &[String], px: u32) -> Result<Bitmap, PreviewError> {
+    let dir = previews_dir();
+    let font_path = dir.join(super::font_filename(family));
+
+    let key: String = Sha256::digest(format!("{family}\n{px}\n{}", lines.join("\n")))
+        .iter()
+        .take(6)
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let stem = font_path.file_stem().and_then(|s| s.to_str()).unwrap_or("font");
+    let pgm_path = dir.join(format!("{stem}-{key}.pgm"));
+    if let Some(bitmap) = std::fs::read(&pgm_path).ok().and_then(|d| Bitmap::from_pgm(&d)) {
+        return Ok(bitmap);
+    }
+
+    let font_data = match std::fs::read(&font_path) {
+        Ok(data) => data,
+        Err(_) => {
+            let source = FontSource::configured()?;
+            if source.is_remote() && net::is_offline() {
+                return Err(PreviewError::Offline);
+            }
+            let (data, _) = source.fetch_font(family)?;
+            std::fs::create_dir_all(&dir).ok();
+            std::fs::write(&font_path, &data).ok();
+            data
+        }
+    };
+
+    let bitmap = rasterize(&font_data, lines, px as f32)?;
+    std::fs::write(&pgm_path, bitmap.to_pgm()).ok();
+    Ok(bitmap)
+}
+
+/// Background worker that loads previews one at a time, always skipping to
+/// the most recently requested font.
+pub struct Previewer {
+    requests: Sender<(String, u32)>,
+    results: Receiver<(String, Result<Bitmap, PreviewError>)>,
+}
+
+impl Previewer {
+    pub fn spawn() -> Self {
+        let (requests, inbox) = mpsc::channel::<(String, u32)>();
+        let (outbox, results) = mpsc::channel();
+        let lines = preview_lines();
+
+        std::thread::spawn(move || {
+            while let Ok(mut request) = inbox.recv() {
+                // Only the latest request matters; the user has moved on.
+                while let Ok(newer) = inbox.try_recv() {
+                    request = newer;
+                }
+                let (family, px) = request;
+                let result = load(&family, &lines, px);
+                if outbox.send((family, result)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Previewer { requests, results }
+    }
+
+    /// Ask for a preview of `family` with text `px` pixels tall.
+    pub fn request(&self, family: &str, px: u32) {
+        let _ = self.requests.send((family.to_string(), px));
+    }
+
+    /// A finished preview, if any.
+    pub fn poll(&self) -> Option<(String, Result<Bitmap, PreviewError>)> {
+        self.results.try_recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pgm_roundtrip() {
+        let bitmap = Bitmap {
+            width: 3,
+            height: 2,
+            pixels: vec![0, 10, 255, 32, 9, 13],
+        };
+        assert_eq!(Bitmap::from_pgm(&bitmap.to_pgm()), Some(bitmap));
+        assert_eq!(Bitmap::from_pgm(b"P6\n1 1\n255\n\0\0\0"), None);
+    }
+}
@@ -0,0 +1,217 @@
+//! Typo-tolerant, popularity-weighted font name ranking.
+//!
+//! Shared by `plyx add font <query>` and the TUI search boxes. Each name gets
+//! a relevance score from the best way it matches the query:
+//!
+//! | Match                                   | Base score     |
+//! |-----------------------------------------|----------------|
+//! | exact                                   | 1000           |
+//! | whole name within a typo or two         | 900 − 40/typo  |
+//! | prefix                                  | 800            |
+//! | word prefix ("sans" → "Open Sans")      | 760            |
+//! | substring                               | 650            |
+//! | some word(s) within a typo or two       | 500 − 40/typo  |
+//! | subsequence ("rbto" → "Roboto")         | 400 − 5/gap    |
+//!
+//! Popularity then subtracts up to ~90 (logarithmic in catalog position), so
+//! popular fonts win within a tier and a very popular word-prefix match can
+//! outrank an obscure prefix match.
+
+/// Score at or above which a match is treated as the name the user meant.
+/// Covers exact matches and whole-name typos, but not mere prefixes.
+const CONFIDENT: i32 = 820;
+
+/// Relevance of `name` for the lowercased `query`, ignoring popularity.
+/// `None` if it doesn't match at all.
+pub fn score(name: &str, query: &str) -> Option<i32> {
+    if query.is_empty() {
+        return Some(0);
+    }
+    let name = name.to_lowercase();
+    let max_typos = max_typos(query);
+
+    if name == query {
+        return Some(1000);
+    }
+    let whole = edit_distance(&name, query);
+    if whole <= max_typos {
+        return Some(900 - 40 * whole as i32);
+    }
+    if name.starts_with(query) {
+        return Some(800);
+    }
+    if name.split_whitespace().any(|w| w.starts_with(query)) {
+        return Some(760);
+    }
+    if name.contains(query) {
+        return Some(650);
+    }
+    if let Some(d) = word_distance(&name, query).filter(|d| *d <= max_typos) {
+        return Some(500 - 40 * d as i32);
+    }
+    subsequence_gaps(&name, query).map(|gaps| (400 - 5 * gaps as i32).max(300))
+}
+
+/// Rank `items` (popularity-sorted) against the lowercased `query`.
+///
+/// Returns `(index, score)` pairs, best first; ties keep catalog order.
+pub fn rank<T>(items: &[T], query: &str, name_of: impl Fn(&T) -> &str) -> Vec<(usize, i32)> {
+    let mut ranked: Vec<(usize, i32)> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(i, item)| {
+            score(name_of(item), query).map(|s| (i, s - popularity_penalty(i)))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked
+}
+
+/// Outcome of resolving a free-form query to one font.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Resolution<'a, T> {
+    /// Exactly one font is clearly meant.
+    Found(&'a T),
+    /// Several fonts match about equally well (best first, at most `limit`).
+    Ambiguous(Vec<&'a T>),
+    NotFound,
+}
+
+/// Pick the font a non-interactive query refers to.
+///
+/// A single result, or a best result that is exact or a near-typo of the
+/// whole name while the runner-up isn't, counts as found. Anything else is
+/// ambiguous and the caller should ask for an exact name.
+pub fn resolve<'a, T>(
+    ranked: &[(usize, i32)],
+    items: &'a [T],
+    limit: usize,
+) -> Resolution<'a, T> {
+    match ranked {
+        [] => Resolution::NotFound,
+        [(i, _)] => Resolution::Found(&items[*i]),
+        [(i, best), (_, second), ..] => {
+            let raw_best = best + popularity_penalty(*i);
+            if raw_best >= CONFIDENT && *second + popularity_penalty(ranked[1].0) < CONFIDENT {
+                Resolution::Found(&items[*i])
+            } else {
+                Resolution::Ambiguous(ranked.iter().take(limit).map(|(i, _)| &items[*i]).collect())
+            }
+        }
+    }
+}
+
+fn popularity_penalty(index: usize) -> i32 {
+    (12.0 * (1.0 + index as f64).ln()) as i32
+}
+
+/// How many typos a query of this length may contain.
+fn max_typos(query: &str) -> usize {
+    match query.chars().count() {
+        0..=3 => 0,
+        4..=7 => 1,
+        _ => 2,
+    }
+}
+
+/// Smallest edit distance between the query and any run of the same number
+/// of consecutive words in `name`.
+fn word_distance(name: &str, query: &str) -> Option<usize> {
+    let words: Vec<&str> = name.split_whitespace().collect();
+    let n = query.split_whitespace().count().max(1);
+    if words.len() < n {
+        return None;
+    }
+    words
+        .windows(n)
+        .map(|w| edit_distance(&w.join(" "), query))
+        .min()
+}
+
+/// Number of skipped characters if `query` is a subsequence of `name`.
+fn subsequence_gaps(name: &str, query: &str) -> Option<usize> {
+    let mut chars = name.chars();
+    let mut gaps = 0;
+    let mut started = false;
+    for q in query.chars() {
+        loop {
+            let c = chars.next()?;
+            if c == q {
+                started = true;
+                break;
+            }
+            if started {
+                gaps += 1;
+            }
+        }
+    }
+    Some(gaps)
+}
+
+/// Optimal-string-alignment distance (Levenshtein plus adjacent swaps).
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    for (j, cell) in d[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1)
+                .min(d[i][j - 1] + 1)
+                .min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1);
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FONTS: &[&str] = &[
+        "Roboto",
+        "Open Sans",
+        "Noto Sans JP",
+        "Roboto Condensed",
+        "Roboto Mono",
+        "PT Sans",
+    ];
+
+    fn resolve_query(query: &str) -> Resolution<'static, &'static str> {
+        let ranked = rank(FONTS, query, |f| f);
+        resolve(&ranked, FONTS, 5)
+    }
+
+    #[test]
+    fn test_typos_and_ranking() {
+        assert_eq!(edit_distance("robto", "roboto"), 1);
+        assert_eq!(edit_distance("rboot", "roboto"), 2);
+
+        let ranked = rank(FONTS, "robto", |f| f);
+        assert_eq!(FONTS[ranked[0].0], "Roboto");
+        let ranked = rank(FONTS, "mono", |f| f);
+        assert_eq!(FONTS[ranked[0].0], "Roboto Mono");
+        assert!(rank(FONTS, "zzz", |f| f).is_empty());
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve_query("robto"), Resolution::Found(&"Roboto"));
+        assert_eq!(resolve_query("open sans"), Resolution::Found(&"Open Sans"));
+        assert_eq!(resolve_query("condensed"), Resolution::Found(&"Roboto Condensed"));
+        match resolve_query("sans") {
+            Resolution::Ambiguous(c) => assert_eq!(c[0], &"Open Sans"),
+            other => panic!("expected ambiguous, got {other:?}"),
+        }
+        assert_eq!(resolve_query("qqq"), Resolution::NotFound);
+    }
+}
@@ -0,0 +1,290 @@
+//! Font providers — where the catalog and font files come from.
+//!
+//! The source is chosen by the `PLYX_FONT_SOURCE` env var, then by
+//! `font-source` in `[package.metadata.plyx]`, and defaults to Google Fonts.
+//! A source is written as `kind[:location]`:
+//!
+//! | Spec                         | Catalog                  | Font files                                  |
+//! |------------------------------|--------------------------|---------------------------------------------|
+//! | `google[:<css2 url>]`        | plyx's GitHub list       | Google Fonts CSS API → fonts.gstatic.com    |
+//! | `fontsource[:<cdn url>]`     | plyx's GitHub list       | `<cdn>/<id>@latest/latin-400-normal.ttf`    |
+//! | `mirror:<dir or url>`        | `<root>/fontlist.json`   | `<root>/<family_name>.ttf`                  |
+//!
+//! A mirror root may be a local directory or an `http(s)://` URL, so a
+//! corporate mirror and a local test stand-in work the same way. The catalog
+//! URL of the first two can be overridden with `PLYX_FONT_LIST_URL`.
+
+use std::path::{Path, PathBuf};
+
+use crate::net::{self, Progress};
+use crate::config;
+
+const FONT_LIST_URL: &str =
+    "https://raw.githubusercontent.com/TheRedDeveloper/plyx/refs/heads/main/fontlist-v2.json";
+const GOOGLE_CSS_URL: &str = "https://fonts.googleapis.com/css2";
+const FONTSOURCE_CDN_URL: &str = "https://cdn.jsdelivr.net/fontsource/fonts";
+
+/// Largest font file plyx will download.
+pub(crate) const MAX_FONT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// A configured font provider.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FontSource {
+    /// Google Fonts, via the `css2` API (or a proxy of it).
+    Google { css_url: String },
+    /// A Fontsource-style CDN serving `@fontsource` packages.
+    Fontsource { cdn_url: String },
+    /// A plain directory or HTTP root with `fontlist.json` and `.ttf` files.
+    Mirror { root: String },
+}
+
+impl FontSource {
+    /// The source selected by `PLYX_FONT_SOURCE` or project config.
+    pub fn configured() -> Result<Self, String> {
+        if let Ok(spec) = std::env::var("PLYX_FONT_SOURCE") {
+            if !spec.trim().is_empty() {
+                return Self::parse(&spec);
+            }
+        }
+        match config::get_str("font-source") {
+            Some(spec) => Self::parse(&spec),
+            None => Ok(Self::default()),
+        }
+    }
+
+    /// Parse a `kind[:location]` spec.
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        let spec = spec.trim();
+        let (kind, location) = match spec.split_once(':') {
+            // `mirror:https://…` — only split on the first colon.
+            Some((k, rest)) if !k.contains('/') => (k, Some(rest.trim())),
+            _ => (spec, None),
+        };
+        let location = location.filter(|l| !l.is_empty());
+
+        match kind.to_lowercase().as_str() {
+            "google" => Ok(FontSource::Google {
+                css_url: location.unwrap_or(GOOGLE_CSS_URL).to_string(),
+            }),
+            "fontsource" => Ok(FontSource::Fontsource {
+                cdn_url: location
+                    .unwrap_or(FONTSOURCE_CDN_URL)
+                    .trim_end_matches('/')
+                    .to_string(),
+            }),
+            "mirror" => match location {
+                Some(root) => Ok(FontSource::Mirror {
+                    root: root.trim_end_matches('/').to_string(),
+                }),
+                None => Err("Font source 'mirror' needs a location, e.g. mirror:/srv/fonts".to_string()),
+            },
+            _ => Err(format!(
+                "Unknown font source '{spec}'. Use google, fontsource or mirror:<dir or url>."
+            )),
+        }
+    }
+
+    /// Short human-readable name for progress messages.
+    pub fn name(&self) -> &str {
+        match self {
+            FontSource::Google { .. } => "Google Fonts",
+            FontSource::Fontsource { .. } => "Fontsource",
+            FontSource::Mirror { .. } => "font mirror",
+        }
+    }
+
+    /// Whether this source needs the network (a local mirror doesn't).
+    pub fn is_remote(&self) -> bool {
+        match self {
+            FontSource::Mirror { root } => is_url(root),
+            _ => true,
+        }
+    }
+
+    /// Where the popularity-sorted catalog lives (URL or path).
+    pub fn catalog_location(&self) -> String {
+        match self {
+            FontSource::Mirror { root } => format!("{root}/fontlist.json"),
+            _ => std::env::var("PLYX_FONT_LIST_URL").unwrap_or_else(|_| FONT_LIST_URL.to_string()),
+        }
+    }
+
+    /// Fetch the raw catalog JSON.
+    pub fn fetch_catalog(&self) -> Result<String, String> {
+        let bytes = fetch(&self.catalog_location(), MAX_FONT_BYTES)
+            .map_err(|e| format!("Failed to fetch font list: {e}"))?;
+        String::from_utf8(bytes).map_err(|e| format!("Font list is not UTF-8: {e}"))
+    }
+
+    /// Download the regular (400) weight of `family` into `dest_dir`.
+    ///
+    /// Returns the saved path and the URL (or path) it was fetched from.
+    pub fn download(&self, family: &str, dest_dir: &Path) -> Result<(PathBuf, String), String> {
+        println!("Downloading {family}...");
+        let (dest_path, url) = self.download_to(family, dest_dir, &mut |_, _| {})?;
+        println!("  Saved to {}", dest_path.display());
+        Ok((dest_path, url))
+    }
+
+    /// Like [`download`](Self::download), without printing, reporting
+    /// progress to `progress` instead. Nothing is left behind on failure.
+    pub fn download_to(
+        &self,
+        family: &str,
+        dest_dir: &Path,
+        progress: Progress<'_>,
+    ) -> Result<(PathBuf, String), String> {
+        if self.is_remote() {
+            net::require_online(&format!("Downloading {family}"))?;
+        }
+
+        std::fs::create_dir_all(dest_dir)
+            .map_err(|e| format!("Failed to create {}: {e}", dest_dir.display()))?;
+
+        let url = self.font_url(family)?;
+        let dest_path = dest_dir.join(super::font_filename(family));
+        fetch_to_file(&url, &dest_path, MAX_FONT_BYTES, progress)
+            .map_err(|e| format!("Failed to download font file: {e}"))?;
+        Ok((dest_path, url))
+    }
+
+    /// Fetch the regular weight of `family` into memory, with its URL.
+    pub fn fetch_font(&self, family: &str) -> Result<(Vec<u8>, String), String> {
+        let url = self.font_url(family)?;
+        let bytes =
+            fetch(&url, MAX_FONT_BYTES).map_err(|e| format!("Failed to download font file: {e}"))?;
+        Ok((bytes, url))
+    }
+
+    /// Resolve the `.ttf` URL (or path) for the regular weight of `family`.
+    fn font_url(&self, family: &str) -> Result<String, String> {
+        match self {
+            FontSource::Google { css_url } => google_ttf_url(css_url, family),
+            FontSource::Fontsource { cdn_url } => Ok(format!(
+                "{cdn_url}/{}@latest/latin-400-normal.ttf",
+                fontsource_id(family)
+            )),
+            FontSource::Mirror { root } => Ok(format!("{root}/{}", super::font_filename(family))),
+        }
+    }
+}
+
+impl Default for FontSource {
+    fn default() -> Self {
+        FontSource::Google {
+            css_url: GOOGLE_CSS_URL.to_string(),
+        }
+    }
+}
+
+/// Fontsource package id: "Open Sans" → "open-sans".
+fn fontsource_id(family: &str) -> String {
+    family.to_lowercase().replace(' ', "-")
+}
+
+/// Ask the Google Fonts CSS API for the direct `.ttf` URL of `family`.
+fn google_ttf_url(css_url: &str, family: &str) -> Result<String, String> {
+    google_css_ttf_url(css_url, family, "")
+}
+
+/// Like [`google_ttf_url`], with extra query parameters (`&icon_names=…`).
+pub(crate) fn google_css_ttf_url(css_url: &str, family: &str, extra: &str) -> Result<String, String> {
+    // Using a User-Agent without woff2 support makes Google serve direct
+    // .ttf URLs.
+    let url = format!("{css_url}?family={}{extra}", family.replace(' ', "+"));
+    let css = net::get_string(&url, &[("User-Agent", "plyx/0.1")], MAX_FONT_BYTES)
+        .map_err(|e| format!("Failed to fetch font CSS for {family}: {e}"))?;
+
+    super::extract_ttf_url(&css)
+        .map(|s| s.to_string())
+        .ok_or_else(|| format!("No .ttf URL found in CSS for {family}. CSS:\n{css}"))
+}
+
+fn is_url(location: &str) -> bool {
+    location.starts_with("http://") || location.starts_with("https://")
+}
+
+/// Read a URL or local path (`file://` prefix allowed) into memory.
+pub(crate) fn fetch(location: &str, limit: u64) -> Result<Vec<u8>, String> {
+    if is_url(location) {
+        net::get_bytes(location, &[], limit)
+    } else {
+        let path = location.strip_prefix("file://").unwrap_or(location);
+        std::fs::read(path).map_err(|e| format!("{path}: {e}"))
+    }
+}
+
+/// Save a URL or local path as `dest`, never leaving a half-written file
+/// behind. Stops with [`net::CANCELLED`] once Ctrl-C is pressed (see
+/// [`net::CancelOnCtrlC`]).
+pub(crate) fn fetch_to_file(
+    location: &str,
+    dest: &Path,
+    limit: u64,
+    progress: Progress<'_>,
+) -> Result<(), String> {
+    if is_url(location) {
+        return net::Download::new(location, dest)
+            .limit(limit)
+            .progress(progress)
+            .run();
+    }
+    let path = location.strip_prefix("file://").unwrap_or(location);
+    let size = std::fs::metadata(path).map_err(|e| format!("{path}: {e}"))?.len();
+    if size > limit {
+        return Err(format!("{path}: larger than {}", net::human_size(limit)));
+    }
+    let part = net::part_path(dest);
+    std::fs::copy(path, &part)
+        .and_then(|_| std::fs::rename(&part, dest))
+        .map_err(|e| {
+            let _ = std::fs::remove_file(&part);
+            format!("Failed to copy {path} to {}: {e}", dest.display())
+        })?;
+    progress(size, Some(size));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_specs() {
+        assert_eq!(FontSource::parse("google").unwrap(), FontSource::default());
+        assert_eq!(
+            FontSource::parse("mirror:https://fonts.corp.example/plyx/").unwrap(),
+            FontSource::Mirror {
+                root: "https://fonts.corp.example/plyx".to_string()
+            }
+        );
+        assert_eq!(
+            FontSource::parse("fontsource").unwrap(),
+            FontSource::Fontsource {
+                cdn_url: FONTSOURCE_CDN_URL.to_string()
+            }
+        );
+        assert!(FontSource::parse("mirror").is_err());
+        assert!(FontSource::parse("dropbox").is_err());
+    }
+
+    #[test]
+    fn test_local_mirror() {
+        let root = std::env::temp_dir().join("plyx_test_mirror");
+        let dest = root.join("out");
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(&root).unwrap();
+        std::fs::write(root.join("fontlist.json"), r#"["Open Sans"]"#).unwrap();
+        std::fs::write(root.join("open_sans.ttf"), b"not really a font").unwrap();
+
+        let source = FontSource::parse(&format!("mirror:{}", root.display())).unwrap();
+        assert!(!source.is_remote());
+        assert_eq!(source.fetch_catalog().unwrap(), r#"["Open Sans"]"#);
+
+        let (path, url) = source.download("Open Sans", &dest).unwrap();
+        assert_eq!(url, format!("{}/open_sans.ttf", root.display()));
+        assert_eq!(std::fs::read(path).unwrap(), b"not really a font");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
@@ -1,8 +1,11 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 use clap_complete::Shell;
+use std::path::PathBuf;
 
 mod commands;
+pub(crate) mod config;
 pub mod fonts;
+pub mod net;
 pub(crate) mod templates;
 pub(crate) mod tui;
 
@@ -10,6 +13,10 @@ pub(crate) mod tui;
 #[derive(Parser)]
 #[command(name = "plyx", version, about, long_about = None, disable_help_subcommand = true)]
 struct Cli {
+    /// Never touch the network (also enabled by PLYX_OFFLINE=1).
+    #[arg(long, global = true)]
+    offline: bool,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
@@ -42,10 +49,62 @@ enum Command {
     },
 
     /// Build for web (WASM).
+    #[command(group(ArgGroup::new("live").args(["serve", "watch"]).multiple(true)))]
     Web {
         /// Non-interactive CI mode.
         #[arg(long)]
         auto: bool,
+
+        /// Serve the build over HTTP after building.
+        #[arg(long)]
+        serve: bool,
+
+        /// Rebuild on changes to src/, assets/, shaders/ or index.html and
+        /// reload the browser. Implies --serve.
+        #[arg(long)]
+        watch: bool,
+
+        /// Optimise for download size: set the release profile (lto,
+        /// opt-level "z", …) and run wasm-opt -Oz if installed.
+        #[arg(long)]
+        size: bool,
+
+        /// Write precompressed .gz and .br copies of app.wasm, the JS
+        /// bundle and assets for static hosts.
+        #[arg(long)]
+        compress: bool,
+
+        /// Make an installable, offline-capable app: write a web app
+        /// manifest and a service worker.
+        #[arg(long)]
+        pwa: bool,
+
+        /// Give app.wasm and ply_bundle.js content-hashed names (e.g.
+        /// app.3f9a1c.wasm) and write asset-manifest.json.
+        #[arg(long)]
+        hash: bool,
+
+        /// Also write <crate>.html: one self-contained file with the JS
+        /// inlined and app.wasm and assets embedded, for sharing demos.
+        #[arg(long, conflicts_with = "pwa")]
+        single_file: bool,
+
+        /// Empty the output directory (if plyx created it) once cargo has
+        /// built the project.
+        #[arg(long)]
+        clean: bool,
+
+        /// Where to put the build.
+        #[arg(long, default_value = commands::web::DEFAULT_OUT_DIR)]
+        out_dir: PathBuf,
+
+        /// Address to serve on; 0.0.0.0 makes it reachable from your LAN.
+        #[arg(long, default_value = "127.0.0.1", requires = "live")]
+        host: String,
+
+        /// Port to serve on.
+        #[arg(long, default_value_t = commands::web::DEFAULT_PORT, requires = "live")]
+        port: u16,
     },
 
     /// Build for iOS (simulator or device).
@@ -63,6 +122,19 @@ enum Command {
         auto: bool,
     },
 
+    /// List and manage the fonts in assets/fonts.
+    Fonts {
+        #[command(subcommand)]
+        action: Option<FontsAction>,
+    },
+
+    /// Write THIRD_PARTY_NOTICES for the fonts in assets/fonts.
+    Licenses {
+        /// Output file (default: assets/THIRD_PARTY_NOTICES).
+        #[arg(long)]
+        out: Option<std::path::PathBuf>,
+    },
+
     /// Generate shell completions.
     Completions {
         /// Shell to generate completions for.
@@ -89,9 +161,40 @@ enum Command {
     },
 }
 
+#[derive(Subcommand)]
+enum FontsAction {
+    /// Show installed fonts (the default).
+    List,
+
+    /// Delete a font and its license.
+    Remove {
+        /// Family or file name, e.g. "open sans" or open_sans.ttf.
+        #[arg(required = true)]
+        name: Vec<String>,
+
+        /// Remove even if src/ still uses it.
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Restore missing fonts from plyx.lock and check their hashes.
+    Sync,
+
+    /// Make a font the DEFAULT_FONT in src/main.rs.
+    Default {
+        /// Family or file name of the installed font.
+        #[arg(required = true)]
+        name: Vec<String>,
+    },
+}
+
 fn main() {
     let cli = Cli::parse();
 
+    if cli.offline {
+        net::set_offline();
+    }
+
     match cli.command {
         None => commands::help::run(),
         Some(Command::Init) => commands::init::run(),
@@ -99,10 +202,49 @@ fn main() {
         Some(Command::Apk { native, install, auto }) => {
             commands::apk::run(native, install, auto);
         }
-        Some(Command::Web { auto }) => commands::web::run(auto),
+        Some(Command::Web {
+            auto,
+            serve,
+            watch,
+            size,
+            compress,
+            pwa,
+            hash,
+            single_file,
+            clean,
+            out_dir,
+            host,
+            port,
+        }) => commands::web::run(commands::web::Options {
+            auto,
+            serve,
+            watch,
+            size,
+            compress,
+            pwa,
+            hash,
+            single_file,
+            clean,
+            out_dir,
+            host,
+            port,
+        }),
         Some(Command::Ios { device, actions, auto }) => {
             commands::ios::run(device, actions, auto);
         }
+        Some(Command::Fonts { action }) => {
+            use commands::fonts::Action;
+            commands::fonts::run(match action {
+                None | Some(FontsAction::List) => Action::List,
+                Some(FontsAction::Remove { name, force }) => Action::Remove {
+                    name: name.join(" "),
+                    force,
+                },
+                Some(FontsAction::Default { name }) => Action::Default { name: name.join(" ") },
+                Some(FontsAction::Sync) => Action::Sync,
+            });
+        }
+        Some(Command::Licenses { out }) => commands::licenses::run(out),
         Some(Command::Completions { shell, install }) => {
             commands::completions::run(shell, install);
         }
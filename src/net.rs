@@ -0,0 +1,553 @@
+//! Network policy shared by every command.
+//!
+//! `plyx --offline` (or `PLYX_OFFLINE=1`) disables all network access.
+//! Code that can degrade gracefully checks [`is_offline`]; code that cannot
+//! calls [`require_online`] to fail with a clear message instead of a
+//! connection error.
+//!
+//! Every HTTP request goes through [`agent`], which applies timeouts,
+//! proxies and custom CA bundles. [`get_bytes`] and [`Download`] add
+//! retries with backoff; [`Download`] also resumes large files and checks
+//! their SHA-256.
+//!
+//! Long downloads hold a [`CancelOnCtrlC`] guard and poll [`is_cancelled`],
+//! so Ctrl-C stops them cleanly instead of leaving half-written files.
+
+use std::io::{IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+
+static OFFLINE: AtomicBool = AtomicBool::new(false);
+
+/// Force offline mode for the rest of the process (set from `--offline`).
+pub fn set_offline() {
+    OFFLINE.store(true, Ordering::Relaxed);
+}
+
+/// Whether network access is disabled, via `--offline` or `PLYX_OFFLINE`.
+pub fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed) || env_flag("PLYX_OFFLINE")
+}
+
+/// Fail with a readable error if `what` would need the network in offline mode.
+pub fn require_online(what: &str) -> Result<(), String> {
+    if is_offline() {
+        return Err(format!(
+            "{what} requires network access, but plyx is in offline mode \
+             (--offline / PLYX_OFFLINE)."
+        ));
+    }
+    Ok(())
+}
+
+/// Treat any non-empty value other than `0`/`false`/`no` as set.
+fn env_flag(name: &str) -> bool {
+    match std::env::var(name) {
+        Ok(v) => {
+            let v = v.trim().to_lowercase();
+            !v.is_empty() && v != "0" && v != "false" && v != "no"
+        }
+        Err(_) => false,
+    }
+}
+
+// ── Cancellation ────────────────────────────────────────────────────────
+
+/// Error message of an operation stopped by Ctrl-C.
+pub const CANCELLED: &str = "Cancelled.";
+
+fn cancel_flag() -> &'static Arc<AtomicBool> {
+    static FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+    FLAG.get_or_init(|| Arc::new(AtomicBool::new(false)))
+}
+
+/// Whether Ctrl-C was pressed while a [`CancelOnCtrlC`] guard was alive.
+pub fn is_cancelled() -> bool {
+    cancel_flag().load(Ordering::Relaxed)
+}
+
+/// While alive, Ctrl-C sets [`is_cancelled`] instead of killing plyx.
+/// Dropping it restores the default behaviour.
+pub struct CancelOnCtrlC {
+    #[cfg(unix)]
+    id: Option<signal_hook::SigId>,
+}
+
+impl CancelOnCtrlC {
+    pub fn install() -> Self {
+        cancel_flag().store(false, Ordering::Relaxed);
+        #[cfg(unix)]
+        {
+            let id = signal_hook::flag::register(signal_hook::consts::SIGINT, cancel_flag().clone())
+                .ok();
+            CancelOnCtrlC { id }
+        }
+        #[cfg(not(unix))]
+        CancelOnCtrlC {}
+    }
+}
+
+impl Drop for CancelOnCtrlC {
+    fn drop(&mut self) {
+        #[cfg(unix)]
+        if let Some(id) = self.id.take() {
+            signal_hook::low_level::unregister(id);
+        }
+    }
+}
+
+// ── HTTP ────────────────────────────────────────────────────────────────
+
+/// How long to wait for a connection, and then for response headers.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(15);
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Tries per request, including the first.
+const ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry; doubled for each one after.
+const BACKOFF: Duration = Duration::from_millis(500);
+
+/// Download progress callback: bytes received so far, and the total size if
+/// the server sent one.
+pub type Progress<'a> = &'a mut dyn FnMut(u64, Option<u64>);
+
+/// The HTTP client every download goes through.
+///
+/// It uses `HTTPS_PROXY` / `ALL_PROXY` / `HTTP_PROXY` and skips hosts in
+/// `NO_PROXY`. It trusts the platform's certificates, or only those in
+/// `PLYX_CA_BUNDLE` (or `SSL_CERT_FILE`) when one is set. Non-2xx statuses
+/// are returned as responses, not errors, so callers can handle `206` and
+/// retry `5xx`.
+pub fn agent() -> ureq::Agent {
+    static AGENT: OnceLock<ureq::Agent> = OnceLock::new();
+    AGENT
+        .get_or_init(|| {
+            let root_certs = match ca_bundle() {
+                Ok(Some(certs)) => ureq::tls::RootCerts::new_with_certs(&certs),
+                Ok(None) => ureq::tls::RootCerts::PlatformVerifier,
+                Err(e) => {
+                    eprintln!("Warning: {e}; using the system certificates.");
+                    ureq::tls::RootCerts::PlatformVerifier
+                }
+            };
+            ureq::Agent::config_builder()
+                .tls_config(ureq::tls::TlsConfig::builder().root_certs(root_certs).build())
+                .proxy(ureq::Proxy::try_from_env())
+                .timeout_connect(Some(CONNECT_TIMEOUT))
+                .timeout_recv_response(Some(RESPONSE_TIMEOUT))
+                .http_status_as_error(false)
+                .user_agent(concat!("plyx/", env!("CARGO_PKG_VERSION")))
+                .build()
+                .new_agent()
+        })
+        .clone()
+}
+
+/// Certificates from `PLYX_CA_BUNDLE` or `SSL_CERT_FILE`, if either is set.
+fn ca_bundle() -> Result<Option<Vec<ureq::tls::Certificate<'static>>>, String> {
+    let Some(path) = ["PLYX_CA_BUNDLE", "SSL_CERT_FILE"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|v| !v.trim().is_empty()))
+    else {
+        return Ok(None);
+    };
+    let pem = std::fs::read(&path).map_err(|e| format!("Failed to read CA bundle {path}: {e}"))?;
+    let certs: Vec<_> = ureq::tls::parse_pem(&pem)
+        .filter_map(|item| match item {
+            Ok(ureq::tls::PemItem::Certificate(cert)) => Some(cert),
+            _ => None,
+        })
+        .collect();
+    if certs.is_empty() {
+        return Err(format!("CA bundle {path} contains no certificates"));
+    }
+    Ok(Some(certs))
+}
+
+/// Why an attempt failed: worth another try, or not.
+enum Failure {
+    Transient(String),
+    Fatal(String),
+}
+
+/// Run `attempt` until it succeeds, fails for good, runs out of tries, or
+/// Ctrl-C is pressed. Waits with exponential backoff between tries.
+fn with_retries<T>(url: &str, mut attempt: impl FnMut() -> Result<T, Failure>) -> Result<T, String> {
+    require_online(url)?;
+    let mut delay = BACKOFF;
+    for n in 1..=ATTEMPTS {
+        if is_cancelled() {
+            return Err(CANCELLED.to_string());
+        }
+        match attempt() {
+            Ok(value) => return Ok(value),
+            Err(Failure::Fatal(e)) => return Err(e),
+            Err(Failure::Transient(e)) if n == ATTEMPTS || is_cancelled() => return Err(e),
+            Err(Failure::Transient(_)) => {
+                std::thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+    }
+    unreachable!("the last attempt always returns")
+}
+
+/// One GET request. Connection problems and `408`/`429`/`5xx` are transient.
+fn send(url: &str, headers: &[(&str, &str)]) -> Result<ureq::http::Response<ureq::Body>, Failure> {
+    let mut request = agent().get(url);
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    match request.call() {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            match status {
+                200..=299 => Ok(response),
+                408 | 429 | 500..=599 => Err(Failure::Transient(format!("{url}: http status {status}"))),
+                _ => Err(Failure::Fatal(format!("{url}: http status {status}"))),
+            }
+        }
+        Err(e @ (ureq::Error::Io(_) | ureq::Error::Timeout(_) | ureq::Error::ConnectionFailed)) => {
+            Err(Failure::Transient(format!("{url}: {e}")))
+        }
+        Err(e) => Err(Failure::Fatal(format!("{url}: {e}"))),
+    }
+}
+
+/// GET `url` and read the whole body, up to `limit` bytes.
+pub fn get_bytes(url: &str, headers: &[(&str, &str)], limit: u64) -> Result<Vec<u8>, String> {
+    with_retries(url, || {
+        let mut response = send(url, headers)?;
+        response
+            .body_mut()
+            .with_config()
+            .limit(limit)
+            .read_to_vec()
+            .map_err(|e| match e {
+                ureq::Error::BodyExceedsLimit(_) => Failure::Fatal(format!("{url}: {e}")),
+                e => Failure::Transient(format!("{url}: {e}")),
+            })
+    })
+}
+
+/// GET `url` as text.
+pub fn get_string(url: &str, headers: &[(&str, &str)], limit: u64) -> Result<String, String> {
+    let bytes = get_bytes(url, headers, limit)?;
+    String::from_utf8(bytes).map_err(|e| format!("{url}: response is not UTF-8: {e}"))
+}
+
+/// A file download: streamed to `<dest>.part`, renamed into place once
+/// complete (and verified, with [`sha256`](Self::sha256)).
+///
+/// ```ignore
+/// let mut bar = net::ProgressBar::new("SDK tools");
+/// let result = net::Download::new(url, &zip)
+///     .sha256(CMDLINE_TOOLS_SHA256)
+///     .resume()
+///     .progress(&mut |done, total| bar.update(done, total))
+///     .run();
+/// bar.finish();
+/// ```
+pub struct Download<'a> {
+    url: &'a str,
+    dest: &'a Path,
+    sha256: Option<&'a str>,
+    limit: Option<u64>,
+    resume: bool,
+    progress: Option<Progress<'a>>,
+}
+
+impl<'a> Download<'a> {
+    pub fn new(url: &'a str, dest: &'a Path) -> Self {
+        Download {
+            url,
+            dest,
+            sha256: None,
+            limit: None,
+            resume: false,
+            progress: None,
+        }
+    }
+
+    /// Fail (and discard the file) unless it has this SHA-256 (hex).
+    pub fn sha256(mut self, hex: &'a str) -> Self {
+        self.sha256 = Some(hex);
+        self
+    }
+
+    /// Fail once the file grows past `bytes`.
+    pub fn limit(mut self, bytes: u64) -> Self {
+        self.limit = Some(bytes);
+        self
+    }
+
+    /// Keep `<dest>.part` when interrupted, and continue it with a `Range`
+    /// request next time instead of starting over. For large files.
+    pub fn resume(mut self) -> Self {
+        self.resume = true;
+        self
+    }
+
+    pub fn progress(mut self, progress: Progress<'a>) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    pub fn run(mut self) -> Result<(), String> {
+        let part = part_path(self.dest);
+        if !self.resume {
+            let _ = std::fs::remove_file(&part);
+        }
+
+        let url = self.url;
+        let result = with_retries(url, || self.attempt(&part)).and_then(|()| self.verify(&part));
+        match result {
+            Ok(()) => std::fs::rename(&part, self.dest)
+                .map_err(|e| format!("Failed to write {}: {e}", self.dest.display())),
+            Err(e) => {
+                if !self.resume {
+                    let _ = std::fs::remove_file(&part);
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Fetch whatever `part` is still missing, appending to it.
+    fn attempt(&mut self, part: &Path) -> Result<(), Failure> {
+        let url = self.url;
+        let offset = std::fs::metadata(part).map_or(0, |m| m.len());
+        let range = format!("bytes={offset}-");
+        let headers: &[(&str, &str)] = if offset > 0 { &[("Range", &range)] } else { &[] };
+
+        let response = match send(url, headers) {
+            // The part is complete or stale; start over.
+            Err(Failure::Fatal(_)) if offset > 0 => {
+                let _ = std::fs::remove_file(part);
+                return Err(Failure::Transient(format!("{url}: resume rejected")));
+            }
+            other => other?,
+        };
+        let resumed = response.status().as_u16() == 206 && offset > 0;
+        let start = if resumed { offset } else { 0 };
+        let total = response.body().content_length().map(|len| start + len);
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(part)
+            .map_err(|e| Failure::Fatal(format!("Failed to create {}: {e}", part.display())))?;
+        let mut reader = response.into_body().into_with_config().limit(u64::MAX).reader();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut done = start;
+        loop {
+            if is_cancelled() {
+                return Err(Failure::Fatal(CANCELLED.to_string()));
+            }
+            let n = reader
+                .read(&mut buf)
+                .map_err(|e| Failure::Transient(format!("{url}: {e}")))?;
+            if n == 0 {
+                break;
+            }
+            file.write_all(&buf[..n])
+                .map_err(|e| Failure::Fatal(format!("Failed to write {}: {e}", part.display())))?;
+            done += n as u64;
+            if let Some(limit) = self.limit.filter(|l| done > *l) {
+                let _ = std::fs::remove_file(part);
+                return Err(Failure::Fatal(format!("{url}: larger than {}", human_size(limit))));
+            }
+            if let Some(progress) = self.progress.as_mut() {
+                progress(done, total);
+            }
+        }
+        match total {
+            Some(total) if done < total => Err(Failure::Transient(format!(
+                "{url}: connection closed after {} of {}",
+                human_size(done),
+                human_size(total)
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    fn verify(&self, part: &Path) -> Result<(), String> {
+        let Some(expected) = self.sha256 else { return Ok(()) };
+        let actual = sha256_file(part)?;
+        if actual.eq_ignore_ascii_case(expected) {
+            Ok(())
+        } else {
+            let _ = std::fs::remove_file(part);
+            Err(format!(
+                "{}: checksum mismatch (expected sha256 {expected}, got {actual})",
+                self.url
+            ))
+        }
+    }
+}
+
+/// `<path>.part`.
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut part = path.as_os_str().to_owned();
+    part.push(".part");
+    PathBuf::from(part)
+}
+
+/// Lowercase hex SHA-256 of a file, read in chunks.
+pub fn sha256_file(path: &Path) -> Result<String, String> {
+    let mut file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+    Ok(hasher.finalize().iter().map(|b| format!("{b:02x}")).collect())
+}
+
+// ── Progress bar ────────────────────────────────────────────────────────
+
+/// A one-line progress bar on stderr, for big downloads. Draws nothing when
+/// stderr isn't a terminal.
+pub struct ProgressBar {
+    label: String,
+    live: bool,
+    started: Instant,
+    last_draw: Option<Instant>,
+}
+
+impl ProgressBar {
+    pub fn new(label: &str) -> Self {
+        ProgressBar {
+            label: label.to_string(),
+            live: std::io::stderr().is_terminal(),
+            started: Instant::now(),
+            last_draw: None,
+        }
+    }
+
+    pub fn update(&mut self, done: u64, total: Option<u64>) {
+        if !self.live || self.last_draw.is_some_and(|t| t.elapsed() < Duration::from_millis(100)) {
+            return;
+        }
+        self.last_draw = Some(Instant::now());
+        let rate = done as f64 / self.started.elapsed().as_secs_f64().max(0.001);
+        let line = match total.filter(|t| *t > 0) {
+            Some(total) => {
+                const WIDTH: u64 = 24;
+                let filled = (done.min(total) * WIDTH / total) as usize;
+                format!(
+                    "{} [{}{}] {:>3}%  {} / {}  {}/s",
+                    self.label,
+                    "#".repeat(filled),
+                    " ".repeat(WIDTH as usize - filled),
+                    done.min(total) * 100 / total,
+                    human_size(done),
+                    human_size(total),
+                    human_size(rate as u64)
+                )
+            }
+            None => format!("{}  {}  {}/s", self.label, human_size(done), human_size(rate as u64)),
+        };
+        eprint!("\r  {line}\x1b[K");
+        let _ = std::io::stderr().flush();
+    }
+
+    /// End the bar's line.
+    pub fn finish(&mut self) {
+        if self.last_draw.is_some() {
+            eprintln!();
+        }
+    }
+}
+
+/// "512 KB", "1.4 MB".
+pub fn human_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Serve `body` on a local port. The first `failures` requests get a
+    /// 503; `Range` requests get a 206. Returns the base URL and the `Range`
+    /// header of every request.
+    fn serve(body: Vec<u8>, failures: usize) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/file", server.server_addr().to_ip().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        std::thread::spawn(move || {
+            for (n, request) in server.incoming_requests().enumerate() {
+                let range = request
+                    .headers()
+                    .iter()
+                    .find(|h| h.field.equiv("Range"))
+                    .map(|h| h.value.to_string());
+                log.lock().unwrap().push(range.clone());
+                let response = if n < failures {
+                    tiny_http::Response::from_data(Vec::new()).with_status_code(503)
+                } else if let Some(start) = range
+                    .and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse::<usize>().ok())
+                {
+                    tiny_http::Response::from_data(body[start..].to_vec()).with_status_code(206)
+                } else {
+                    tiny_http::Response::from_data(body.clone())
+                };
+                let _ = request.respond(response.with_chunked_threshold(usize::MAX));
+            }
+        });
+        (url, seen)
+    }
+
+    #[test]
+    fn test_download_resumes_and_verifies() {
+        let body: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let sha = format!("{:x}", Sha256::digest(&body));
+        let (url, seen) = serve(body.clone(), 1);
+
+        let dir = std::env::temp_dir().join("plyx_test_net_download");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("archive.zip");
+        std::fs::write(part_path(&dest), &body[..1000]).unwrap();
+
+        let mut last = 0;
+        Download::new(&url, &dest)
+            .resume()
+            .sha256(&sha)
+            .progress(&mut |done, total| {
+                assert_eq!(total, Some(body.len() as u64));
+                last = done;
+            })
+            .run()
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+        assert_eq!(last, body.len() as u64);
+        assert!(!part_path(&dest).exists());
+        // A 503, then a successful retry that resumed the partial file.
+        let range = Some("bytes=1000-".to_string());
+        assert_eq!(*seen.lock().unwrap(), [range.clone(), range]);
+
+        let wrong = "0".repeat(64);
+        let err = Download::new(&url, &dest).sha256(&wrong).run().unwrap_err();
+        assert!(err.contains("checksum mismatch"), "{err}");
+        assert!(!part_path(&dest).exists());
+
+        assert_eq!(get_bytes(&url, &[], 1 << 20).unwrap(), body);
+        assert!(get_bytes(&url, &[], 10).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -101,12 +101,15 @@ ply-engine = { version = "1.1", default-features = false, features = ["shader-bu
     toml
 }
 
-pub(crate) const INDEX_HTML: &str = r#"<!DOCTYPE html>
+/// The default `index.html`. Its `{{VARIABLES}}` are filled in on every
+/// `plyx web` build (see `commands::web::page`).
+pub(crate) const INDEX_HTML: &str = r##"<!DOCTYPE html>
 <html lang="en">
 <head>
     <meta charset="utf-8">
     <meta name="viewport" content="width=device-width, initial-scale=1" />
     <title>{{TITLE}}</title>
+    {{HEAD}}
     <style>
         html,
         body,
@@ -120,20 +123,121 @@ pub(crate) const INDEX_HTML: &str = r#"<!DOCTYPE html>
             background: black;
             z-index: 0;
         }
+        #loading {
+            position: absolute;
+            inset: 0;
+            z-index: 1;
+            display: flex;
+            flex-direction: column;
+            align-items: center;
+            justify-content: center;
+            gap: 16px;
+            background: black;
+            color: #ccc;
+            font: 16px sans-serif;
+        }
+        #loading.done {
+            display: none;
+        }
+        #loading.tap {
+            cursor: pointer;
+        }
+        #progress {
+            width: 200px;
+            height: 4px;
+            border-radius: 2px;
+            background: #333;
+            overflow: hidden;
+        }
+        #progress div {
+            width: 0;
+            height: 100%;
+            background: #ccc;
+        }
     </style>
 </head>
 <body>
     <canvas id="glcanvas" tabindex="0"></canvas>
+    <div id="loading">
+        <div id="progress"><div></div></div>
+        <span id="status">Loading…</span>
+    </div>
     <script src="ply_bundle.js"></script>
-    <script>load("app.wasm");</script>
+    <script>
+        (() => {
+            // Browsers only let pages play sound after a tap or click.
+            const tapToStart = {{TAP_TO_START}};
+            const loading = document.getElementById("loading");
+            const bar = document.querySelector("#progress div");
+            const status = document.getElementById("status");
+
+            // ply_bundle.js fetches the wasm itself; count the bytes as
+            // they arrive to drive the progress bar. A compressed response's
+            // Content-Length doesn't match the bytes read, so it gets none.
+            const fetch = window.fetch;
+            window.fetch = (url, ...rest) =>
+                fetch(url, ...rest).then((response) => {
+                    const total = Number(response.headers.get("Content-Length"));
+                    const encoded = response.headers.has("Content-Encoding");
+                    if (!String(url).endsWith(".wasm") || !response.body || !total || encoded) {
+                        return response;
+                    }
+                    const reader = response.body.getReader();
+                    let loaded = 0;
+                    const body = new ReadableStream({
+                        async pull(controller) {
+                            const { done, value } = await reader.read();
+                            if (done) {
+                                status.textContent = "Starting…";
+                                controller.close();
+                                return;
+                            }
+                            loaded += value.byteLength;
+                            bar.style.width = Math.min(100, (loaded / total) * 100) + "%";
+                            controller.enqueue(value);
+                        },
+                    });
+                    return new Response(body, { status: response.status, headers: response.headers });
+                });
+
+            // The app is running once ply_bundle.js has its memory.
+            const whenRunning = () => {
+                if (wasm_memory) {
+                    loading.classList.add("done");
+                } else {
+                    requestAnimationFrame(whenRunning);
+                }
+            };
+            const start = () => {
+                load("app.wasm");
+                whenRunning();
+            };
+
+            if (tapToStart) {
+                loading.classList.add("tap");
+                status.textContent = "Tap to start";
+                loading.addEventListener("click", () => {
+                    loading.classList.remove("tap");
+                    status.textContent = "Loading…";
+                    start();
+                }, { once: true });
+            } else {
+                start();
+            }
+        })();
+    </script>
 </body>
 </html>
-"#;
+"##;
 
-pub(crate) fn generate_main_rs(font_filename: &str) -> String {
+/// `src/main.rs` of a new project; `font_ident` is the default font's
+/// constant in the generated `src/fonts.rs`.
+pub(crate) fn generate_main_rs(font_ident: &str) -> String {
     format!(
         r#"use ply_engine::prelude::*;
 
+mod fonts;
+
 fn window_conf() -> macroquad::conf::Conf {{
     macroquad::conf::Conf {{
         miniquad_conf: miniquad::conf::Conf {{
@@ -156,8 +260,8 @@ fn window_conf() -> macroquad::conf::Conf {{
 
 #[macroquad::main(window_conf)]
 async fn main() {{
-    static DEFAULT_FONT: FontAsset = FontAsset::Path("assets/fonts/{font_filename}");
-    let mut ply = Ply::<()>::new(&DEFAULT_FONT).await;
+    static DEFAULT_FONT: &FontAsset = &fonts::{font_ident};
+    let mut ply = Ply::<()>::new(DEFAULT_FONT).await;
 
     loop {{
         clear_background(BLACK);
@@ -10,8 +10,12 @@ use crossterm::{
     style::{self, Stylize},
     terminal, ExecutableCommand, QueueableCommand,
 };
+use std::collections::HashMap;
 use std::io::{self, Write};
 
+use crate::fonts::preview::{Bitmap, PreviewError, Previewer};
+use crate::fonts::{self, FontInfo, FontQuery};
+
 // ── Helpers ──────────────────────────────────────────────────────────────
 
 /// Enter raw mode and hide the cursor; returns a guard that restores state
@@ -117,6 +121,25 @@ fn confirm_inner(prompt: &str) -> io::Result<bool> {
     }
 }
 
+// ── redraw_lines ─────────────────────────────────────────────────────────
+
+/// Replace the `prev` lines printed by the last call with `lines`, for
+/// progress displays that update in place. Returns how many lines are now
+/// on screen.
+pub fn redraw_lines(lines: &[String], prev: usize) -> io::Result<usize> {
+    let mut out = io::stdout();
+    move_up(&mut out, prev as u16)?;
+    move_to_start_and_clear(&mut out)?;
+    let cols = terminal::size().map_or(80, |(c, _)| c as usize);
+    for line in lines {
+        let line: String = line.chars().take(cols.saturating_sub(1)).collect();
+        out.queue(style::Print(line))?;
+        out.queue(style::Print("\n"))?;
+    }
+    out.flush()?;
+    Ok(lines.len())
+}
+
 // ── text_input ───────────────────────────────────────────────────────────
 
 /// Prompt for a single line of text with an optional default.
@@ -198,31 +221,70 @@ const VISIBLE_RESULTS: usize = 6;
 ///
 /// The top filtered result is always highlighted (blue). Press Enter/Space
 /// to pick it. No arrow-key navigation — you refine by typing.
-pub fn search_select(prompt: &str, items: &[String], help: &str) -> Result<String, String> {
-    search_select_inner(prompt, items, help).map_err(|e| e.to_string())
+///
+/// `catalog` supplies font metadata for `mono:`/`script:` style filters
+/// when it has any (see [`FontQuery::for_catalog`]); items are matched to it by name, ignoring a trailing
+/// `(…)` note such as `(Default)`. Pass `&[]` for plain lists.
+pub fn search_select(
+    prompt: &str,
+    items: &[String],
+    catalog: &[FontInfo],
+    help: &str,
+) -> Result<String, String> {
+    search_select_inner(prompt, items, catalog, help).map_err(|e| e.to_string())
 }
 
-fn search_select_inner(prompt: &str, items: &[String], help: &str) -> io::Result<String> {
+fn search_select_inner(
+    prompt: &str,
+    items: &[String],
+    catalog: &[FontInfo],
+    help: &str,
+) -> io::Result<String> {
     // Keep cursor visible so user sees where they type in the search box.
     let _guard = RawGuard::enter(false)?;
     let mut out = io::stdout();
     let mut query = String::new();
     let mut last_lines: u16 = 0;
 
-    last_lines = render_search(&mut out, prompt, &query, items, &[], help, last_lines)?;
+    // Only font lists get a preview.
+    let mut preview = (!catalog.is_empty()).then(PreviewPane::new).flatten();
+    if let Some(pane) = &mut preview {
+        pane.show(filter(items, &query, catalog).first().map(|s| label_family(s)));
+    }
+
+    last_lines = render_search(
+        &mut out, prompt, &query, items, catalog, &[], help, preview.as_ref(), last_lines,
+    )?;
 
     loop {
+        // Wake up now and then so a finished preview gets drawn.
+        if !event::poll(std::time::Duration::from_millis(100))? {
+            if preview.as_mut().is_some_and(PreviewPane::update) {
+                last_lines = render_search(
+                    &mut out, prompt, &query, items, catalog, &[], help, preview.as_ref(),
+                    last_lines,
+                )?;
+            }
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             if key.kind != KeyEventKind::Press { continue; }
             if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                if let Some(pane) = &preview {
+                    pane.clear_images(&mut out)?;
+                    out.flush()?;
+                }
                 drop(_guard);
                 std::process::exit(130);
             }
             match key.code {
                 KeyCode::Enter => {
-                    let filtered = filter(items, &query);
+                    let filtered = filter(items, &query, catalog);
                     if let Some(selected) = filtered.first() {
                         // Clear widget and print confirmed line
+                        if let Some(pane) = &preview {
+                            pane.clear_images(&mut out)?;
+                        }
                         move_up(&mut out, last_lines)?;
                         move_to_start_and_clear(&mut out)?;
                         print_confirm(&mut out, prompt, selected)?;
@@ -237,46 +299,73 @@ fn search_select_inner(prompt: &str, items: &[String], help: &str) -> io::Result
                 }
                 _ => {}
             }
-            last_lines =
-                render_search(&mut out, prompt, &query, items, &[], help, last_lines)?;
+            if let Some(pane) = &mut preview {
+                pane.show(filter(items, &query, catalog).first().map(|s| label_family(s)));
+            }
+            last_lines = render_search(
+                &mut out, prompt, &query, items, catalog, &[], help, preview.as_ref(), last_lines,
+            )?;
         }
     }
 }
 
-fn filter<'a>(items: &'a [String], query: &str) -> Vec<&'a String> {
-    if query.is_empty() {
+fn filter<'a>(items: &'a [String], query: &str, catalog: &[FontInfo]) -> Vec<&'a String> {
+    if query.trim().is_empty() {
         return items.iter().collect();
     }
-    let q = query.to_lowercase();
-    // Split into starts-with and contains-but-not-starts-with,
-    // preserving the original popularity order within each group.
-    let mut starts: Vec<&String> = Vec::new();
-    let mut contains: Vec<&String> = Vec::new();
-    for item in items {
-        let lower = item.to_lowercase();
-        if lower.starts_with(&q) {
-            starts.push(item);
-        } else if lower.contains(&q) {
-            contains.push(item);
-        }
+    let query = FontQuery::for_catalog(query, catalog);
+
+    let by_name: HashMap<String, &FontInfo> = if query.has_filters() {
+        catalog.iter().map(|f| (f.family.to_lowercase(), f)).collect()
+    } else {
+        HashMap::new()
+    };
+
+    let candidates: Vec<&String> = items
+        .iter()
+        .filter(|item| {
+            !query.has_filters()
+                || query.matches_filters(
+                    by_name.get(&label_family(item).to_lowercase()).copied(),
+                )
+        })
+        .collect();
+
+    // Same typo-tolerant ranking as `plyx add font`; ties keep the original
+    // (popularity) order.
+    fonts::search::rank(&candidates, &query.text, |item| label_family(item))
+        .into_iter()
+        .map(|(i, _)| candidates[i])
+        .collect()
+}
+
+/// Strip a trailing `(…)` note from a list label: "Lexend (Default)" → "Lexend".
+fn label_family(label: &str) -> &str {
+    match label.rfind(" (") {
+        Some(i) if label.ends_with(')') => &label[..i],
+        _ => label,
     }
-    starts.extend(contains);
-    starts
 }
 
 /// Render the search widget. Returns total line count (below the starting
 /// row) so the next redraw knows how far to move up.
+#[allow(clippy::too_many_arguments)]
 fn render_search(
     out: &mut io::Stdout,
     prompt: &str,
     query: &str,
     items: &[String],
+    catalog: &[FontInfo],
     selected: &[String],
     help: &str,
+    preview: Option<&PreviewPane>,
     prev_lines: u16,
 ) -> io::Result<u16> {
     // Go back to the top of the widget
     move_up(out, prev_lines)?;
+    if let Some(pane) = preview {
+        pane.clear_images(out)?;
+    }
     move_to_start_and_clear(out)?;
 
     // Prompt line
@@ -290,7 +379,7 @@ fn render_search(
     }
     out.queue(style::Print("\r\n"))?;
 
-    let filtered = filter(items, query);
+    let filtered = filter(items, query, catalog);
     let shown: Vec<&String> = filtered.iter().take(VISIBLE_RESULTS).copied().collect();
 
     let mut lines: u16 = 1; // prompt line
@@ -313,6 +402,10 @@ fn render_search(
         lines += 1;
     }
 
+    if let Some(pane) = preview {
+        lines += pane.draw(out)?;
+    }
+
     // Move cursor back to the prompt line so it sits after the query text.
     // After printing `lines` lines of \r\n, cursor is `lines` rows below start.
     move_up(out, lines)?;
@@ -324,6 +417,310 @@ fn render_search(
     Ok(0)
 }
 
+// ── Font preview ────────────────────────────────────────────────────────
+
+/// How the terminal can show the preview image.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Graphics {
+    Kitty,
+    Sixel,
+    /// Unicode quadrant blocks, two by two pixels per cell.
+    Blocks,
+}
+
+/// Pick a graphics protocol: `PLYX_PREVIEW` (`kitty`, `sixel`, `blocks` or
+/// `off`) wins, otherwise guess from the terminal's environment variables.
+/// `None` means no preview.
+fn detect_graphics() -> Option<Graphics> {
+    let env = |key: &str| std::env::var(key).unwrap_or_default();
+    match env("PLYX_PREVIEW").to_lowercase().as_str() {
+        "off" | "none" | "0" | "false" => return None,
+        "kitty" => return Some(Graphics::Kitty),
+        "sixel" => return Some(Graphics::Sixel),
+        "blocks" => return Some(Graphics::Blocks),
+        _ => {}
+    }
+
+    let term = env("TERM");
+    let program = env("TERM_PROGRAM");
+    if !env("KITTY_WINDOW_ID").is_empty()
+        || term.contains("kitty")
+        || matches!(program.as_str(), "WezTerm" | "ghostty")
+    {
+        Some(Graphics::Kitty)
+    } else if term.contains("sixel")
+        || term.starts_with("foot")
+        || term.starts_with("mlterm")
+        || program == "iTerm.app"
+    {
+        Some(Graphics::Sixel)
+    } else {
+        Some(Graphics::Blocks)
+    }
+}
+
+/// The preview area under the font list.
+struct PreviewPane {
+    graphics: Graphics,
+    previewer: Previewer,
+    /// Text height in pixels requested from the rasteriser.
+    px: u32,
+    /// Terminal cell size in pixels (for image protocols).
+    cell: (u32, u32),
+    family: Option<String>,
+    state: PreviewState,
+}
+
+enum PreviewState {
+    Empty,
+    Loading,
+    Ready(Bitmap),
+    /// Not cached, and fetching is off.
+    Offline,
+    Unavailable,
+}
+
+impl PreviewPane {
+    fn new() -> Option<Self> {
+        let graphics = detect_graphics()?;
+        let cell = terminal::window_size()
+            .ok()
+            .filter(|w| w.width > 0 && w.height > 0 && w.columns > 0 && w.rows > 0)
+            .map(|w| {
+                (
+                    u32::from(w.width / w.columns),
+                    u32::from(w.height / w.rows),
+                )
+            })
+            .unwrap_or((10, 20));
+        let px = match graphics {
+            // Six rows of quadrant blocks per line of text.
+            Graphics::Blocks => 12,
+            Graphics::Kitty | Graphics::Sixel => cell.1 * 2,
+        };
+        Some(PreviewPane {
+            graphics,
+            previewer: Previewer::spawn(),
+            px,
+            cell,
+            family: None,
+            state: PreviewState::Empty,
+        })
+    }
+
+    /// Switch to `family` (the highlighted item), requesting it if new.
+    fn show(&mut self, family: Option<&str>) {
+        if self.family.as_deref() == family {
+            return;
+        }
+        self.family = family.map(str::to_string);
+        self.state = match family {
+            Some(f) => {
+                self.previewer.request(f, self.px);
+                PreviewState::Loading
+            }
+            None => PreviewState::Empty,
+        };
+    }
+
+    /// Take finished previews; true if the current one changed.
+    fn update(&mut self) -> bool {
+        let mut changed = false;
+        while let Some((family, result)) = self.previewer.poll() {
+            if self.family.as_deref() == Some(family.as_str()) {
+                self.state = match result {
+                    Ok(bitmap) => PreviewState::Ready(bitmap),
+                    Err(PreviewError::Offline) => PreviewState::Offline,
+                    Err(PreviewError::Failed(_)) => PreviewState::Unavailable,
+                };
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Remove the kitty image left from the previous frame (it lives
+    /// outside the text grid, so clearing lines doesn't erase it). Other
+    /// images on screen are left alone.
+    fn clear_images(&self, out: &mut impl Write) -> io::Result<()> {
+        if self.graphics == Graphics::Kitty {
+            out.queue(style::Print(format!("\x1b_Ga=d,d=i,i={KITTY_IMAGE_ID},q=2\x1b\\")))?;
+        }
+        Ok(())
+    }
+
+    /// Draw the preview at the cursor; returns the number of lines used.
+    fn draw(&self, out: &mut impl Write) -> io::Result<u16> {
+        let note = |out: &mut dyn Write, text: &str| -> io::Result<u16> {
+            out.queue(style::Print(style::style(format!("  {text}")).dark_grey()))?;
+            out.queue(style::Print("\r\n"))?;
+            Ok(1)
+        };
+        let bitmap = match &self.state {
+            PreviewState::Empty => return Ok(0),
+            PreviewState::Loading => return note(out, "Loading preview…"),
+            PreviewState::Offline => return note(out, "(no preview offline)"),
+            PreviewState::Unavailable => return note(out, "(no preview available)"),
+            PreviewState::Ready(bitmap) => bitmap,
+        };
+
+        out.queue(style::Print("\r\n"))?;
+        let max_cols = terminal::size().map_or(80, |(c, _)| c).saturating_sub(4) as usize;
+        if self.graphics == Graphics::Blocks {
+            let rows = quadrant_blocks(bitmap, max_cols);
+            for row in &rows {
+                out.queue(style::Print(format!("  {row}\r\n")))?;
+            }
+            return Ok(rows.len() as u16 + 1);
+        }
+
+        let bitmap = crop(bitmap, max_cols * self.cell.0 as usize);
+        let rows = bitmap.height.div_ceil(self.cell.1.max(1) as usize) as u16;
+        let image = match self.graphics {
+            Graphics::Kitty => kitty_image(&bitmap, rows),
+            _ => sixel_image(&bitmap),
+        };
+        // Reserve the rows first so the image can't scroll the screen, then
+        // draw it in place and step back below it.
+        for _ in 0..rows {
+            out.queue(style::Print("\r\n"))?;
+        }
+        move_up(out, rows)?;
+        out.queue(cursor::MoveToColumn(2))?;
+        out.queue(cursor::SavePosition)?;
+        out.queue(style::Print(image))?;
+        out.queue(cursor::RestorePosition)?;
+        out.queue(cursor::MoveDown(rows))?;
+        out.queue(cursor::MoveToColumn(0))?;
+        Ok(rows + 1)
+    }
+}
+
+/// Ink colour for image previews; readable on dark and light backgrounds.
+const PREVIEW_INK: (u8, u8, u8) = (90, 150, 255);
+
+/// Id of the kitty preview image, so it can be deleted on its own.
+const KITTY_IMAGE_ID: u32 = 0x706c7978;
+
+/// Keep at most `max_width` pixels of each row.
+fn crop(bitmap: &Bitmap, max_width: usize) -> Bitmap {
+    let width = bitmap.width.min(max_width.max(1));
+    let pixels = (0..bitmap.height)
+        .flat_map(|y| (0..width).map(move |x| bitmap.get(x, y)))
+        .collect();
+    Bitmap {
+        width,
+        height: bitmap.height,
+        pixels,
+    }
+}
+
+/// Render with quadrant block characters, at most `max_cols` wide.
+fn quadrant_blocks(bitmap: &Bitmap, max_cols: usize) -> Vec<String> {
+    const QUADRANTS: [char; 16] = [
+        ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+    ];
+    let ink = |x, y| bitmap.get(x, y) >= 96;
+    let cols = bitmap.width.div_ceil(2).min(max_cols);
+    (0..bitmap.height.div_ceil(2))
+        .map(|row| {
+            let (y0, y1) = (row * 2, row * 2 + 1);
+            (0..cols)
+                .map(|col| {
+                    let (x0, x1) = (col * 2, col * 2 + 1);
+                    let bits = usize::from(ink(x0, y0))
+                        | usize::from(ink(x1, y0)) << 1
+                        | usize::from(ink(x0, y1)) << 2
+                        | usize::from(ink(x1, y1)) << 3;
+                    QUADRANTS[bits]
+                })
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect()
+}
+
+/// Kitty graphics protocol: RGBA pixels, scaled to `rows` cells, cursor
+/// left in place.
+fn kitty_image(bitmap: &Bitmap, rows: u16) -> String {
+    use base64::Engine;
+
+    let (r, g, b) = PREVIEW_INK;
+    let rgba: Vec<u8> = bitmap.pixels.iter().flat_map(|&a| [r, g, b, a]).collect();
+    let data = base64::engine::general_purpose::STANDARD.encode(rgba);
+
+    let chunks: Vec<&[u8]> = data.as_bytes().chunks(4096).collect();
+    let mut out = String::new();
+    for (i, chunk) in chunks.iter().enumerate() {
+        let more = u8::from(i + 1 < chunks.len());
+        let chunk = std::str::from_utf8(chunk).unwrap_or_default();
+        if i == 0 {
+            out.push_str(&format!(
+                "\x1b_Ga=T,f=32,i={KITTY_IMAGE_ID},s={},v={},r={rows},C=1,q=2,m={more};{chunk}\x1b\\",
+                bitmap.width, bitmap.height
+            ));
+        } else {
+            out.push_str(&format!("\x1b_Gm={more};{chunk}\x1b\\"));
+        }
+    }
+    out
+}
+
+/// Sixel image with four ink levels on a transparent background.
+fn sixel_image(bitmap: &Bitmap) -> String {
+    const LEVELS: u8 = 4;
+    let (r, g, b) = PREVIEW_INK;
+    let pct = |c: u8, level: u8| u32::from(c) * 100 * u32::from(level) / (255 * u32::from(LEVELS));
+
+    // P2=1: pixels we don't paint stay transparent.
+    let mut out = format!("\x1bP0;1;0q\"1;1;{};{}", bitmap.width, bitmap.height);
+    for level in 1..=LEVELS {
+        out.push_str(&format!(
+            "#{level};2;{};{};{}",
+            pct(r, level),
+            pct(g, level),
+            pct(b, level)
+        ));
+    }
+    let level_of = |x, y| -> u8 {
+        let v = bitmap.get(x, y);
+        if v < 32 { 0 } else { (u16::from(v) * u16::from(LEVELS)).div_ceil(256) as u8 }
+    };
+
+    for band in 0..bitmap.height.div_ceil(6) {
+        for level in 1..=LEVELS {
+            let sixels: Vec<u8> = (0..bitmap.width)
+                .map(|x| {
+                    (0..6).fold(0u8, |bits, dy| {
+                        bits | (u8::from(level_of(x, band * 6 + dy) == level) << dy)
+                    })
+                })
+                .collect();
+            if sixels.iter().all(|&s| s == 0) {
+                continue;
+            }
+            out.push_str(&format!("#{level}"));
+            // Run-length encode repeated columns.
+            let mut i = 0;
+            while i < sixels.len() {
+                let run = sixels[i..].iter().take_while(|&&s| s == sixels[i]).count();
+                let ch = char::from(63 + sixels[i]);
+                if run > 3 {
+                    out.push_str(&format!("!{run}{ch}"));
+                } else {
+                    out.extend(std::iter::repeat_n(ch, run));
+                }
+                i += run;
+            }
+            out.push('$');
+        }
+        out.push('-');
+    }
+    out.push_str("\x1b\\");
+    out
+}
+
 // ── feature_select ──────────────────────────────────────────────────────
 
 /// Item in the feature selector: either a toggleable feature or the action
@@ -494,6 +891,7 @@ fn feature_select_inner(
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 fn render_features(
     out: &mut io::Stdout,
     prompt: &str,
@@ -586,15 +984,17 @@ pub struct AddResult {
 /// Combined feature + font add widget for `plyx add`.
 ///
 /// Shows features (with locked ones already checked), a font search bar,
-/// search results, and a single Done! button. Arrow keys navigate between
+/// search results with a preview of the top one, and a single Done! button. Arrow keys navigate between
 /// features, the font search, and Done!.
 ///
+/// `font_items` — the font catalog; the search accepts `mono:`/`script:` style
+/// filters when it has metadata (see [`FontQuery::for_catalog`]).  
 /// `locked_features` — already-enabled feature keys (checked, green, sorry on toggle).  
 /// `installed_fonts` — font names already in assets/fonts/ (green, sorry on add).
 pub fn add_widget(
     prompt: &str,
     features: &[(&str, &str, &str, Option<&str>)],
-    font_items: &[String],
+    font_items: &[FontInfo],
     locked_features: &[&str],
     installed_fonts: &[String],
     help: &str,
@@ -613,13 +1013,15 @@ enum AddCursorPos {
 fn add_widget_inner(
     prompt: &str,
     features: &[(&str, &str, &str, Option<&str>)],
-    font_items: &[String],
+    catalog: &[FontInfo],
     locked_features: &[&str],
     installed_fonts: &[String],
     help: &str,
 ) -> io::Result<AddResult> {
     let _guard = RawGuard::enter(false)?; // cursor visible for font typing
     let mut out = io::stdout();
+    let font_items: Vec<String> = catalog.iter().map(|f| f.family.clone()).collect();
+    let font_items = &font_items[..];
 
     let mut cursor = AddCursorPos::Feature(0);
     let mut feature_checked: Vec<bool> = features
@@ -632,11 +1034,21 @@ fn add_widget_inner(
     let mut font_sorry = false;
     let mut last_lines: u16 = 0;
 
+    // Preview the highlighted font while the font search has the cursor.
+    let mut preview = (!catalog.is_empty()).then(PreviewPane::new).flatten();
+    let highlighted = |cursor: &AddCursorPos, query: &str, sorry: bool| -> Option<String> {
+        if !matches!(cursor, AddCursorPos::FontSearch) || sorry {
+            return None;
+        }
+        filter(font_items, query, catalog).first().map(|s| s.to_string())
+    };
+
     last_lines = render_add(
         &mut out,
         prompt,
         features,
         font_items,
+        catalog,
         locked_features,
         installed_fonts,
         &cursor,
@@ -646,13 +1058,42 @@ fn add_widget_inner(
         sorry_feature,
         font_sorry,
         help,
+        preview.as_ref(),
         last_lines,
     )?;
 
     loop {
+        // Wake up now and then so a finished preview gets drawn.
+        if !event::poll(std::time::Duration::from_millis(100))? {
+            if preview.as_mut().is_some_and(PreviewPane::update) {
+                last_lines = render_add(
+                    &mut out,
+                    prompt,
+                    features,
+                    font_items,
+                    catalog,
+                    locked_features,
+                    installed_fonts,
+                    &cursor,
+                    &feature_checked,
+                    &font_query,
+                    &added_fonts,
+                    sorry_feature,
+                    font_sorry,
+                    help,
+                    preview.as_ref(),
+                    last_lines,
+                )?;
+            }
+            continue;
+        }
         if let Event::Key(key) = event::read()? {
             if key.kind != KeyEventKind::Press { continue; }
             if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+                if let Some(pane) = &preview {
+                    pane.clear_images(&mut out)?;
+                    out.flush()?;
+                }
                 drop(_guard);
                 std::process::exit(130);
             }
@@ -728,10 +1169,10 @@ fn add_widget_inner(
                         font_sorry = false;
                     }
                     KeyCode::Enter => {
-                        let filtered = filter(font_items, &font_query);
+                        let filtered = filter(font_items, &font_query, catalog);
                         if let Some(top) = filtered.first() {
                             let name = (*top).clone();
-                            if installed_fonts.iter().any(|f| f == &name)
+                            if installed_fonts.iter().any(|f| f.eq_ignore_ascii_case(&name))
                                 || added_fonts.iter().any(|f| f == &name)
                             {
                                 font_sorry = true;
@@ -772,6 +1213,9 @@ fn add_widget_inner(
                             .map(|(_, (key, _, _, _))| key.to_string())
                             .collect();
 
+                        if let Some(pane) = &preview {
+                            pane.clear_images(&mut out)?;
+                        }
                         move_up(&mut out, last_lines)?;
                         move_to_start_and_clear(&mut out)?;
 
@@ -807,11 +1251,15 @@ fn add_widget_inner(
                 },
             }
 
+            if let Some(pane) = &mut preview {
+                pane.show(highlighted(&cursor, &font_query, font_sorry).as_deref());
+            }
             last_lines = render_add(
                 &mut out,
                 prompt,
                 features,
                 font_items,
+                catalog,
                 locked_features,
                 installed_fonts,
                 &cursor,
@@ -821,6 +1269,7 @@ fn add_widget_inner(
                 sorry_feature,
                 font_sorry,
                 help,
+                preview.as_ref(),
                 last_lines,
             )?;
         }
@@ -833,6 +1282,7 @@ fn render_add(
     prompt: &str,
     features: &[(&str, &str, &str, Option<&str>)],
     font_items: &[String],
+    catalog: &[FontInfo],
     locked_features: &[&str],
     installed_fonts: &[String],
     cursor: &AddCursorPos,
@@ -842,6 +1292,7 @@ fn render_add(
     sorry_feature: Option<usize>,
     font_sorry: bool,
     help: &str,
+    preview: Option<&PreviewPane>,
     prev_lines: u16,
 ) -> io::Result<u16> {
     // Determine if cursor ends up parked mid-widget (font search) or at the end.
@@ -850,6 +1301,9 @@ fn render_add(
     let park_at_font_search = matches!(cursor, AddCursorPos::FontSearch);
 
     move_up(out, prev_lines)?;
+    if let Some(pane) = preview {
+        pane.clear_images(out)?;
+    }
     move_to_start_and_clear(out)?;
 
     // Prompt
@@ -903,7 +1357,12 @@ fn render_add(
         out.queue(style::Print("Add fonts: "))?;
     }
     if font_query.is_empty() {
-        out.queue(style::Print(style::style("(type to search)").dark_grey()))?;
+        let hint = if fonts::catalog::has_metadata(catalog) {
+            "(type to search, filter with mono: or script:cyrillic)"
+        } else {
+            "(type to search)"
+        };
+        out.queue(style::Print(style::style(hint).dark_grey()))?;
     } else {
         out.queue(style::Print(font_query))?;
     }
@@ -919,10 +1378,10 @@ fn render_add(
         out.queue(style::Print("\r\n"))?;
         lines += 1;
     } else {
-        let filtered = filter(font_items, font_query);
+        let filtered = filter(font_items, font_query, catalog);
         let shown: Vec<&String> = filtered.iter().take(VISIBLE_RESULTS).copied().collect();
         for (i, item) in shown.iter().enumerate() {
-            let is_installed = installed_fonts.iter().any(|f| f == *item);
+            let is_installed = installed_fonts.iter().any(|f| f.eq_ignore_ascii_case(item));
             let is_added = added_fonts.iter().any(|f| f == *item);
             if i == 0 && font_is_cursor {
                 out.queue(style::Print(style::style(format!("    {item}")).blue()))?;
@@ -936,6 +1395,10 @@ fn render_add(
         }
     }
 
+    if let Some(pane) = preview {
+        lines += pane.draw(out)?;
+    }
+
     // ── Done! button
     let done_is_cursor = matches!(cursor, AddCursorPos::Done);
     let done_text = "    > Done!";